@@ -0,0 +1,464 @@
+#[cfg(test)]
+mod blocking_tests {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use resend_client_rs::{
+        blocking::Client,
+        domains::{
+            CreateDomainRequest, CreateDomainResponse, DeleteDomainResponse, DnsRecord,
+            DnsRecordType, DomainDetails, DomainStatus, DomainSummary, EmailDnsRecord,
+            ListDomainsResponse, Region, VerificationOutcome, WaitOptions,
+        },
+        emails::{Email, SendEmailRequest, SendEmailResponse},
+        merge::{MailMerge, Recipient},
+        reqlib::{APIResponse, RateLimitInfo},
+    };
+
+    #[test]
+    fn send_should_work() {
+        let mut server = mockito::Server::new();
+        let base_url = server.url();
+
+        let send_email_response = SendEmailResponse {
+            id: "mock-id".to_string(),
+        };
+
+        let email_post_mock = server
+            .mock("POST", "/emails")
+            .match_header("authorization", "Bearer api-key")
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(serde_json::to_string(&send_email_response).unwrap())
+            .create();
+
+        let mut client = Client::new("api-key");
+        client.email_service.req_client.base_url = url::Url::parse(&base_url[..]).unwrap();
+        let result = client.email_service.send(&SendEmailRequest {
+            subject: "My subject".to_string(),
+            from: "from@domain.com".to_string(),
+            to: vec!["to@domain.com".to_string()],
+            cc: None,
+            bcc: None,
+            reply_to: None,
+            html: None,
+            text: None,
+            tags: None,
+            attachments: None,
+            headers: None,
+        });
+        let data = match result.unwrap() {
+            APIResponse::Success(data) => Some(data),
+            _ => None,
+        };
+        assert_eq!(data.unwrap().id, "mock-id");
+
+        email_post_mock.assert();
+    }
+
+    #[test]
+    fn send_with_meta_should_expose_response_metadata() {
+        let mut server = mockito::Server::new();
+        let base_url = server.url();
+
+        let email_post_mock = server
+            .mock("POST", "/emails")
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_header("x-request-id", "req_123")
+            .with_header("ratelimit-limit", "10")
+            .with_header("ratelimit-remaining", "9")
+            .with_header("ratelimit-reset", "1")
+            .with_body(r#"{"id":"mock-id"}"#)
+            .create();
+
+        let mut client = Client::new("api-key");
+        client.email_service.req_client.base_url = url::Url::parse(&base_url[..]).unwrap();
+        let response = client.email_service.send_with_meta(&SendEmailRequest {
+            subject: "My subject".to_string(),
+            from: "from@domain.com".to_string(),
+            to: vec!["to@domain.com".to_string()],
+            cc: None,
+            bcc: None,
+            reply_to: None,
+            html: None,
+            text: Some("Hello World".to_string()),
+            tags: None,
+            attachments: None,
+            headers: None,
+        });
+        assert!(matches!(response.result, Ok(APIResponse::Success(_))));
+        let meta = response.meta.unwrap();
+        assert_eq!(meta.request_id.as_deref(), Some("req_123"));
+        assert_eq!(
+            meta.rate_limit,
+            Some(RateLimitInfo {
+                limit: 10,
+                remaining: 9,
+                reset: Duration::from_secs(1),
+            })
+        );
+
+        email_post_mock.assert();
+    }
+
+    #[test]
+    fn request_should_send_query_and_parse_response() {
+        let mut server = mockito::Server::new();
+        let base_url = server.url();
+
+        let api_keys_mock = server
+            .mock("GET", "/api-keys")
+            .match_query(mockito::Matcher::UrlEncoded("limit".into(), "1".into()))
+            .match_header("authorization", "Bearer api-key")
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(r#"{"data":[{"id":"key-id","name":"Production"}]}"#)
+            .create();
+
+        let mut client = Client::new("api-key");
+        client.raw_client.base_url = url::Url::parse(&base_url[..]).unwrap();
+        let result = client.request::<(), serde_json::Value>(
+            http::Method::GET,
+            "api-keys",
+            &[("limit", "1")],
+            None,
+        );
+        match result.unwrap() {
+            APIResponse::Success(data) => assert_eq!(data["data"][0]["name"], "Production"),
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        api_keys_mock.assert();
+    }
+
+    #[test]
+    fn get_email_should_work() {
+        let mut server = mockito::Server::new();
+        let base_url = server.url();
+
+        let email_details = Email {
+            id: "id".to_string(),
+            object: "email".to_string(),
+            from: "from@domain.com".to_string(),
+            to: vec!["to@domain.com".to_string()],
+            created_at: "2023-11-19T10:00:00.000Z".to_string(),
+            subject: "My subject".to_string(),
+            html: None,
+            text: None,
+            bcc: None,
+            cc: None,
+            reply_to: None,
+            last_event: "delivered".to_string(),
+        };
+
+        let email_get_mock = server
+            .mock("GET", "/emails/mock-id")
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(serde_json::to_string(&email_details).unwrap())
+            .create();
+
+        let mut client = Client::new("api-key");
+        client.email_service.req_client.base_url = url::Url::parse(&base_url[..]).unwrap();
+        let result = client.email_service.get("mock-id");
+        let data = match result.unwrap() {
+            APIResponse::Success(data) => Some(data),
+            _ => None,
+        };
+        assert_eq!(
+            serde_json::to_string(&email_details).unwrap(),
+            serde_json::to_string(&data.unwrap()).unwrap()
+        );
+
+        email_get_mock.assert();
+    }
+
+    #[test]
+    fn error_response_should_be_returned() {
+        let mut server = mockito::Server::new();
+        let base_url = server.url();
+
+        let email_get_mock = server
+            .mock("GET", "/emails/missing")
+            .with_status(404)
+            .with_header("Content-Type", "application/json")
+            .with_body(r#"{"name":"not_found","status_code":404,"message":"Email not found"}"#)
+            .create();
+
+        let mut client = Client::new("api-key");
+        client.email_service.req_client.base_url = url::Url::parse(&base_url[..]).unwrap();
+        match client.email_service.get("missing") {
+            Err(APIResponse::Error(err)) => assert_eq!(err.status_code, 404),
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        email_get_mock.assert();
+    }
+
+    #[test]
+    fn create_domain_should_work() {
+        let mut server = mockito::Server::new();
+        let base_url = server.url();
+
+        let create_domain_response = CreateDomainResponse {
+            id: "mock-id".into(),
+            created_at: "2023-11-19T10:00:00.000Z".into(),
+            name: "domain.com".into(),
+            region: Region::UsEast1,
+            status: DomainStatus::Pending,
+            dnsProvider: "unknown".into(),
+        };
+
+        let domain_post_mock = server
+            .mock("POST", "/domains")
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(serde_json::to_string(&create_domain_response).unwrap())
+            .create();
+
+        let mut client = Client::new("api-key");
+        client.domain_service.req_client.base_url = url::Url::parse(&base_url[..]).unwrap();
+        let result = client
+            .domain_service
+            .create(&CreateDomainRequest::builder("domain.com").build());
+        let data = match result.unwrap() {
+            APIResponse::Success(data) => Some(data),
+            _ => None,
+        };
+        assert_eq!(
+            serde_json::to_string(&create_domain_response).unwrap(),
+            serde_json::to_string(&data.unwrap()).unwrap(),
+        );
+
+        domain_post_mock.assert();
+    }
+
+    #[test]
+    fn list_domains_should_work() {
+        let mut server = mockito::Server::new();
+        let base_url = server.url();
+
+        let list_domains_response = ListDomainsResponse {
+            data: vec![DomainSummary {
+                id: "d91cd9bd-1176-453e-8fc1-35364d380206".into(),
+                name: "example.com".into(),
+                status: DomainStatus::NotStarted,
+                created_at: "2023-04-26T20:21:26.347412+00:00".into(),
+                region: Region::UsEast1,
+            }],
+        };
+
+        let domains_mock = server
+            .mock("GET", "/domains")
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(serde_json::to_string(&list_domains_response).unwrap())
+            .create();
+
+        let mut client = Client::new("api-key");
+        client.domain_service.req_client.base_url = url::Url::parse(&base_url[..]).unwrap();
+        let result = client.domain_service.list();
+        let data = match result.unwrap() {
+            APIResponse::Success(data) => Some(data),
+            _ => None,
+        };
+        assert_eq!(
+            serde_json::to_string(&list_domains_response).unwrap(),
+            serde_json::to_string(&data.unwrap()).unwrap(),
+        );
+
+        domains_mock.assert();
+    }
+
+    #[test]
+    fn get_domain_should_work() {
+        let mut server = mockito::Server::new();
+        let base_url = server.url();
+
+        let get_domain_response = DomainDetails {
+            object: "domain".into(),
+            id: "d91cd9bd-1176-453e-8fc1-35364d380206".into(),
+            name: "example.com".into(),
+            status: DomainStatus::NotStarted,
+            created_at: "2023-04-26T20:21:26.347412+00:00".into(),
+            region: Region::UsEast1,
+            records: vec![DnsRecord {
+                record: EmailDnsRecord::Spf,
+                name: "send".into(),
+                r#type: DnsRecordType::Mx,
+                ttl: "Auto".into(),
+                status: DomainStatus::NotStarted,
+                value: "feedback-smtp.us-east-1.amazonses.com".into(),
+                priority: Some(10),
+            }],
+        };
+
+        let domains_mock = server
+            .mock("GET", "/domains/mock-id")
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(serde_json::to_string(&get_domain_response).unwrap())
+            .create();
+
+        let mut client = Client::new("api-key");
+        client.domain_service.req_client.base_url = url::Url::parse(&base_url[..]).unwrap();
+        let result = client.domain_service.get("mock-id");
+        let data = match result.unwrap() {
+            APIResponse::Success(data) => Some(data),
+            _ => None,
+        };
+        assert_eq!(
+            serde_json::to_string(&get_domain_response).unwrap(),
+            serde_json::to_string(&data.unwrap()).unwrap(),
+        );
+
+        domains_mock.assert();
+    }
+
+    #[test]
+    fn delete_domain_should_work() {
+        let mut server = mockito::Server::new();
+        let base_url = server.url();
+
+        let delete_response = DeleteDomainResponse {
+            object: "domain".into(),
+            id: "d91cd9bd-1176-453e-8fc1-35364d380206".into(),
+            deleted: true,
+        };
+
+        let domains_mock = server
+            .mock("DELETE", "/domains/mock-id")
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(serde_json::to_string(&delete_response).unwrap())
+            .create();
+
+        let mut client = Client::new("api-key");
+        client.domain_service.req_client.base_url = url::Url::parse(&base_url[..]).unwrap();
+        let result = client.domain_service.delete("mock-id");
+        let data = match result.unwrap() {
+            APIResponse::Success(data) => Some(data),
+            _ => None,
+        };
+        assert!(data.unwrap().deleted);
+
+        domains_mock.assert();
+    }
+
+    #[test]
+    fn wait_until_verified_should_work() {
+        let mut server = mockito::Server::new();
+        let base_url = server.url();
+
+        let pending_domain = DomainDetails {
+            object: "domain".into(),
+            id: "d91cd9bd-1176-453e-8fc1-35364d380206".into(),
+            name: "example.com".into(),
+            status: DomainStatus::Pending,
+            created_at: "2023-04-26T20:21:26.347412+00:00".into(),
+            region: Region::UsEast1,
+            records: vec![DnsRecord {
+                record: EmailDnsRecord::Spf,
+                name: "send".into(),
+                r#type: DnsRecordType::Mx,
+                ttl: "Auto".into(),
+                status: DomainStatus::Pending,
+                value: "feedback-smtp.us-east-1.amazonses.com".into(),
+                priority: Some(10),
+            }],
+        };
+        let pending_mock = server
+            .mock("GET", "/domains/mock-id")
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(serde_json::to_string(&pending_domain).unwrap())
+            .expect(1)
+            .create();
+        let verified_domain = DomainDetails {
+            object: "domain".into(),
+            id: "d91cd9bd-1176-453e-8fc1-35364d380206".into(),
+            name: "example.com".into(),
+            status: DomainStatus::Verified,
+            created_at: "2023-04-26T20:21:26.347412+00:00".into(),
+            region: Region::UsEast1,
+            records: vec![DnsRecord {
+                record: EmailDnsRecord::Spf,
+                name: "send".into(),
+                r#type: DnsRecordType::Mx,
+                ttl: "Auto".into(),
+                status: DomainStatus::Verified,
+                value: "feedback-smtp.us-east-1.amazonses.com".into(),
+                priority: Some(10),
+            }],
+        };
+        let verified_mock = server
+            .mock("GET", "/domains/mock-id")
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(serde_json::to_string(&verified_domain).unwrap())
+            .expect(1)
+            .create();
+
+        let mut client = Client::new("api-key");
+        client.domain_service.req_client.base_url = url::Url::parse(&base_url[..]).unwrap();
+        let result = client.domain_service.wait_until_verified(
+            "mock-id",
+            &WaitOptions {
+                initial_interval: Duration::from_millis(10),
+                max_interval: Duration::from_millis(20),
+                timeout: Duration::from_secs(5),
+            },
+        );
+        assert!(matches!(result.unwrap(), VerificationOutcome::Verified(_)));
+
+        pending_mock.assert();
+        verified_mock.assert();
+    }
+
+    #[test]
+    fn send_merge_should_report_per_recipient() {
+        let mut server = mockito::Server::new();
+        let batch_mock = server
+            .mock("POST", "/emails/batch")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"[{"to":["a@domain.com"],"subject":"Hi Ann"},{"to":["b@domain.com"],"subject":"Hi Bob"}]"#
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(r#"{"data":[{"id":"first-id"},{"id":"second-id"}]}"#)
+            .create();
+
+        let mut client = Client::new("api-key");
+        client.email_service.req_client.base_url = url::Url::parse(&server.url()).unwrap();
+        let merge = MailMerge::new(SendEmailRequest {
+            subject: "Hi {{name}}".to_string(),
+            from: "from@domain.com".to_string(),
+            to: vec![],
+            cc: None,
+            bcc: None,
+            reply_to: None,
+            html: None,
+            text: Some("Hello {{name}}".to_string()),
+            tags: None,
+            attachments: None,
+            headers: None,
+        });
+        let report = client
+            .email_service
+            .send_merge(
+                &merge,
+                [
+                    Recipient::new("a@domain.com", HashMap::from([("name", "Ann")])),
+                    Recipient::new("b@domain.com", HashMap::from([("name", "Bob")])),
+                ],
+            )
+            .unwrap();
+
+        assert!(report.is_ok());
+        assert_eq!(report.results[1].to, "b@domain.com");
+        assert_eq!(report.results[1].result.as_deref().unwrap(), "second-id");
+        batch_mock.assert();
+    }
+}
@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod cassette_tests {
+    use std::sync::Arc;
+
+    use resend_client_rs::{
+        cassette::{Cassette, CassetteTransport},
+        emails::SendEmailRequest,
+        reqlib::{APIResponse, Error},
+        testing::FakeResend,
+        Client,
+    };
+
+    use crate::fixtures::send_email_request;
+
+    fn cassette_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "resend-cassette-{}-{}.json",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[tokio::test]
+    async fn recorded_cassette_should_replay() {
+        let path = cassette_path("round-trip");
+        let fake = FakeResend::new();
+        let recorder = Arc::new(CassetteTransport::record(fake.clone(), &path));
+        let client = Client::with_transport("re_secret_key", recorder.clone());
+
+        let email = SendEmailRequest {
+            from: "Acme <from@domain.com>".to_string(),
+            ..send_email_request()
+        };
+
+        let sent = match client.email_service.send(&email).await.unwrap() {
+            APIResponse::Success(data) => data,
+            other => panic!("unexpected result {:?}", other),
+        };
+
+        let recorded: Cassette = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(recorded.interactions.len(), 1);
+        let request = &recorded.interactions[0].request;
+        assert_eq!(request.headers["authorization"], "Bearer [REDACTED]");
+        let body = request.body.as_ref().unwrap();
+        assert_eq!(body["from"], "Acme <redacted@example.com>");
+        assert_eq!(body["to"][0], "redacted@example.com");
+        assert!(!std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("re_secret_key"));
+
+        let mut replay = Client::new("another-key");
+        replay
+            .email_service
+            .req_client
+            .replay_cassette(&path)
+            .unwrap();
+        // different addresses still match because both sides are redacted
+        let request = SendEmailRequest {
+            to: vec!["someone-else@domain.com".to_string()],
+            ..email
+        };
+        match replay.email_service.send(&request).await.unwrap() {
+            APIResponse::Success(data) => assert_eq!(data.id, sent.id),
+            other => panic!("unexpected result {:?}", other),
+        }
+        // every interaction is only served once
+        assert!(matches!(
+            replay.email_service.send(&request).await,
+            Err(APIResponse::Failure(Error::Transport(_)))
+        ));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn unmatched_request_should_fail_replay() {
+        let path = cassette_path("mismatch");
+        let fake = FakeResend::new();
+        let client = Client::with_transport(
+            "re_secret_key",
+            Arc::new(CassetteTransport::record(fake.clone(), &path)),
+        );
+        client
+            .email_service
+            .send(&send_email_request())
+            .await
+            .unwrap();
+
+        let client = Client::with_transport(
+            "re_secret_key",
+            Arc::new(CassetteTransport::replay(&path).unwrap()),
+        );
+        match client
+            .email_service
+            .send(&SendEmailRequest {
+                text: Some("Goodbye World".to_string()),
+                ..send_email_request()
+            })
+            .await
+        {
+            Err(APIResponse::Failure(Error::Transport(err))) => {
+                assert!(err.to_string().contains("POST /emails"))
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+        assert!(client.domain_service.list().await.is_err());
+        assert_eq!(fake.sent_emails().len(), 1);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
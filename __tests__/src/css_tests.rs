@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod css_tests {
+    use resend_client_rs::{
+        css::CssInliner,
+        emails::{Attachment, SendEmailRequest},
+        plain_text::TextAlternative,
+        reqlib::{APIResponse, Error},
+        testing::FakeResend,
+    };
+
+    use crate::fixtures::send_email_request;
+
+    const HTML: &str = r#"<html><head><style>
+        p { color: red; margin: 0 }
+        .button { background: #000 }
+        @media (max-width: 600px) { p { font-size: 18px } }
+    </style></head><body><p>Hi</p><a class="button" style="padding: 4px" href="https://x.io">Go</a></body></html>"#;
+
+    #[test]
+    fn inline_should_move_rules_and_keep_media_queries() {
+        let html = CssInliner::new().inline(HTML).unwrap();
+        assert!(html.contains(r#"<p style="color: red;margin: 0;">Hi</p>"#));
+        assert!(html.contains(r#"style="background: #000;padding: 4px""#));
+        assert!(html.contains("@media (max-width: 600px)"));
+        assert!(!html.contains(".button {"));
+    }
+
+    #[test]
+    fn inline_should_minify_when_enabled() {
+        let html = CssInliner::new().minify().inline(HTML).unwrap();
+        assert!(html.contains(r#"<p style="color:red;margin:0">Hi</p>"#));
+        assert!(html.contains("@media (max-width: 600px)"));
+    }
+
+    #[test]
+    fn minify_should_collapse_html_whitespace_and_comments() {
+        let html = "<html>\n  <body>\n    <!-- greeting -->\n    <p   class=\"a  b\">Hi   there</p>\n    <!--[if mso]><table><tr><td><![endif]-->\n    <pre>  keep\n    this</pre>\n  </body>\n</html>";
+        assert_eq!(
+            CssInliner::new().minify().inline(html).unwrap(),
+            "<html><head></head><body> <p class=\"a  b\">Hi there</p> <!--[if mso]><table><tr><td><![endif]--> <pre>  keep\n    this</pre> </body></html>"
+        );
+    }
+
+    #[tokio::test]
+    async fn send_should_inline_before_other_interceptors() {
+        let fake = FakeResend::new();
+        let mut client = fake.client();
+        client
+            .email_service
+            .add_interceptor(CssInliner::new().minify());
+        client.email_service.add_interceptor(TextAlternative);
+        client
+            .email_service
+            .send(&SendEmailRequest {
+                html: Some(HTML.to_string()),
+                text: None,
+                ..send_email_request()
+            })
+            .await
+            .unwrap();
+
+        let sent = &fake.sent_emails()[0];
+        assert!(sent
+            .html
+            .as_deref()
+            .unwrap()
+            .contains(r#"style="color:red;margin:0""#));
+        assert_eq!(sent.text.as_deref(), Some("Hi\n\nGo (https://x.io)"));
+    }
+
+    #[tokio::test]
+    async fn send_should_check_size_after_inlining() {
+        // just under 40MB until the styles of the paragraphs are inlined
+        let style = "<style>p { color: red; margin: 0 }</style>";
+        let paragraphs = "<p>x</p>".repeat(50);
+        let filler = "x".repeat(40 * 1024 * 1024 - 1000 - style.len() - paragraphs.len());
+        let html = format!("{}<div>{}</div>{}", style, filler, paragraphs);
+        let email = SendEmailRequest {
+            html: Some(html),
+            text: None,
+            ..send_email_request()
+        };
+        assert!(email.validate().is_ok());
+
+        let fake = FakeResend::new();
+        let mut client = fake.client();
+        client.email_service.add_interceptor(CssInliner::new());
+        match client.email_service.send(&email).await {
+            Err(APIResponse::Failure(Error::Validation(err))) => assert_eq!(err.field, "size"),
+            other => panic!("unexpected response {:?}", other),
+        }
+        assert!(fake.sent_emails().is_empty());
+    }
+
+    #[test]
+    fn size_should_count_attachments_as_serialized() {
+        // 12MB would be 16MB as base64, but the bytes are posted as a JSON array of numbers
+        let content = vec![255u8; 12 * 1024 * 1024];
+        let attachment = Attachment::new("data.bin", content);
+        let email = SendEmailRequest {
+            html: Some("<p>Hi</p>".to_string()),
+            attachments: Some(vec![&attachment]),
+            ..send_email_request()
+        };
+
+        assert_eq!(email.size(), serde_json::to_vec(&email).unwrap().len());
+        match email.validate() {
+            Err(err) => assert_eq!(err.field, "size"),
+            Ok(()) => panic!("the email should be too large"),
+        }
+    }
+}
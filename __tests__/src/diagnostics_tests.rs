@@ -0,0 +1,257 @@
+#[cfg(test)]
+mod diagnostics_tests {
+    use std::{collections::HashMap, future::Future};
+
+    use resend_client_rs::{
+        diagnostics::{check_domain, DnsResolver, RecordCheck, ResolveError, ResolvedRecord},
+        domains::{DnsRecord, DnsRecordType, DomainDetails, DomainStatus, EmailDnsRecord},
+    };
+
+    #[derive(Default)]
+    struct FakeResolver {
+        records: HashMap<(String, String), Result<Vec<ResolvedRecord>, ResolveError>>,
+    }
+
+    impl FakeResolver {
+        fn insert(
+            &mut self,
+            name: &str,
+            record_type: DnsRecordType,
+            result: Result<Vec<ResolvedRecord>, ResolveError>,
+        ) {
+            self.records
+                .insert((name.to_string(), format!("{:?}", record_type)), result);
+        }
+    }
+
+    impl DnsResolver for FakeResolver {
+        fn lookup(
+            &self,
+            name: &str,
+            record_type: &DnsRecordType,
+        ) -> impl Future<Output = Result<Vec<ResolvedRecord>, ResolveError>> + Send {
+            let result = self
+                .records
+                .get(&(name.to_string(), format!("{:?}", record_type)))
+                .cloned()
+                .unwrap_or(Ok(vec![]));
+            async move { result }
+        }
+    }
+
+    fn record(
+        record: EmailDnsRecord,
+        r#type: DnsRecordType,
+        name: &str,
+        value: &str,
+        priority: Option<u16>,
+    ) -> DnsRecord {
+        DnsRecord {
+            record,
+            r#type,
+            name: name.into(),
+            ttl: "Auto".into(),
+            status: DomainStatus::Pending,
+            value: value.into(),
+            priority,
+        }
+    }
+
+    fn domain(records: Vec<DnsRecord>) -> DomainDetails {
+        DomainDetails {
+            object: "domain".into(),
+            id: "mock-id".into(),
+            name: "example.com".into(),
+            status: DomainStatus::Pending,
+            created_at: "2023-04-26T20:21:26.347412+00:00".into(),
+            region: "us-east-1".into(),
+            records,
+        }
+    }
+
+    #[tokio::test]
+    async fn matching_records_should_pass() {
+        let mut resolver = FakeResolver::default();
+        resolver.insert(
+            "send.example.com",
+            DnsRecordType::Mx,
+            Ok(vec![ResolvedRecord::Mx {
+                priority: 10,
+                exchange: "Feedback-SMTP.us-east-1.amazonses.com.".into(),
+            }]),
+        );
+        resolver.insert(
+            "send.example.com",
+            DnsRecordType::Txt,
+            Ok(vec![
+                ResolvedRecord::Txt("google-site-verification=abc".into()),
+                ResolvedRecord::Txt("v=spf1 include:amazonses.com ~all".into()),
+            ]),
+        );
+        resolver.insert(
+            "resend._domainkey.example.com",
+            DnsRecordType::Txt,
+            Ok(vec![ResolvedRecord::Txt(
+                "\"p=MIGfMA0\" \"GCSqGSIb3\"".into(),
+            )]),
+        );
+
+        let report = check_domain(
+            &resolver,
+            &domain(vec![
+                record(
+                    EmailDnsRecord::Spf,
+                    DnsRecordType::Mx,
+                    "send",
+                    "feedback-smtp.us-east-1.amazonses.com",
+                    Some(10),
+                ),
+                record(
+                    EmailDnsRecord::Spf,
+                    DnsRecordType::Txt,
+                    "send",
+                    "\"v=spf1 include:amazonses.com ~all\"",
+                    None,
+                ),
+                record(
+                    EmailDnsRecord::Dkim,
+                    DnsRecordType::Txt,
+                    "resend._domainkey",
+                    "p=MIGfMA0GCSqGSIb3",
+                    None,
+                ),
+            ]),
+        )
+        .await;
+
+        assert!(report.is_ok(), "{:?}", report);
+        assert_eq!(report.records[0].fully_qualified_name, "send.example.com");
+    }
+
+    #[tokio::test]
+    async fn subdomain_records_should_be_looked_up_in_the_parent_zone() {
+        let mut resolver = FakeResolver::default();
+        resolver.insert(
+            "send.mail.example.com",
+            DnsRecordType::Txt,
+            Ok(vec![ResolvedRecord::Txt(
+                "v=spf1 include:amazonses.com ~all".into(),
+            )]),
+        );
+        resolver.insert(
+            "resend._domainkey.mail.example.com",
+            DnsRecordType::Txt,
+            Ok(vec![ResolvedRecord::Txt("p=MIGfMA0GCSqGSIb3".into())]),
+        );
+        let mut domain = domain(vec![
+            record(
+                EmailDnsRecord::Spf,
+                DnsRecordType::Txt,
+                "send.mail",
+                "\"v=spf1 include:amazonses.com ~all\"",
+                None,
+            ),
+            record(
+                EmailDnsRecord::Dkim,
+                DnsRecordType::Txt,
+                "resend._domainkey.mail",
+                "p=MIGfMA0GCSqGSIb3",
+                None,
+            ),
+        ]);
+        domain.name = "mail.example.com".into();
+
+        let report = check_domain(&resolver, &domain).await;
+
+        assert!(report.is_ok(), "{:?}", report);
+        assert_eq!(
+            report.records[0].fully_qualified_name,
+            "send.mail.example.com"
+        );
+        assert_eq!(
+            report.records[1].fully_qualified_name,
+            "resend._domainkey.mail.example.com"
+        );
+    }
+
+    #[tokio::test]
+    async fn wrong_records_should_be_reported() {
+        let mut resolver = FakeResolver::default();
+        resolver.insert(
+            "send.example.com",
+            DnsRecordType::Mx,
+            Ok(vec![ResolvedRecord::Mx {
+                priority: 20,
+                exchange: "feedback-smtp.us-east-1.amazonses.com".into(),
+            }]),
+        );
+        resolver.insert(
+            "resend._domainkey.example.com",
+            DnsRecordType::Cname,
+            Ok(vec![ResolvedRecord::Cname("other.example.net.".into())]),
+        );
+        resolver.insert(
+            "broken.example.com",
+            DnsRecordType::Txt,
+            Err(ResolveError {
+                message: "timed out".into(),
+            }),
+        );
+
+        let report = check_domain(
+            &resolver,
+            &domain(vec![
+                record(
+                    EmailDnsRecord::Spf,
+                    DnsRecordType::Mx,
+                    "send",
+                    "feedback-smtp.us-east-1.amazonses.com",
+                    Some(10),
+                ),
+                record(
+                    EmailDnsRecord::Spf,
+                    DnsRecordType::Txt,
+                    "send.example.com",
+                    "v=spf1 include:amazonses.com ~all",
+                    None,
+                ),
+                record(
+                    EmailDnsRecord::Dkim,
+                    DnsRecordType::Cname,
+                    "resend._domainkey",
+                    "resend.domainkey.amazonses.com",
+                    None,
+                ),
+                record(
+                    EmailDnsRecord::Dkim,
+                    DnsRecordType::Txt,
+                    "broken",
+                    "p=abc",
+                    None,
+                ),
+            ]),
+        )
+        .await;
+
+        assert!(!report.is_ok());
+        assert_eq!(report.problems().count(), 4);
+        assert_eq!(
+            report.records[0].check,
+            RecordCheck::PriorityMismatch {
+                expected: Some(10),
+                found: vec![20],
+            }
+        );
+        assert_eq!(report.records[1].check, RecordCheck::Missing);
+        assert_eq!(
+            report.records[2].check,
+            RecordCheck::Mismatch {
+                found: vec!["other.example.net".into()],
+            }
+        );
+        assert!(matches!(
+            report.records[3].check,
+            RecordCheck::LookupFailed(_)
+        ));
+    }
+}
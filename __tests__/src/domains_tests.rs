@@ -1,25 +1,26 @@
 #[cfg(test)]
 mod domains_tests {
-    use std::vec;
+    use std::{time::Duration, vec};
 
     use resend_client_rs::{
         domains::{
-            CreateDomainRequest, CreateDomainResponse, DnsRecord, DnsRecordType, DomainDetails,
-            DomainStatus, DomainSummary, EmailDnsRecord, ListDomainsResponse, DeleteDomainResponse,
+            CreateDomainRequest, CreateDomainResponse, DeleteDomainResponse, DnsRecord,
+            DnsRecordType, DomainDetails, DomainStatus, DomainSummary, EmailDnsRecord,
+            ListDomainsResponse, Region, VerificationOutcome, WaitOptions,
         },
         Client,
     };
 
     #[tokio::test]
     async fn create_should_work() {
-        let mut server = mockito::Server::new();
+        let mut server = mockito::Server::new_async().await;
         let base_url = server.url();
 
         let create_domain_response = CreateDomainResponse {
             id: "mock-id".into(),
             created_at: "2023-11-19T10:00:00.000Z".into(),
             name: "domain.com".into(),
-            region: "us-east-1".into(),
+            region: Region::UsEast1,
             status: DomainStatus::Pending,
             dnsProvider: "unknown".into(),
         };
@@ -38,6 +39,7 @@ mod domains_tests {
             .create(&CreateDomainRequest {
                 name: "domain.com".into(),
                 region: None,
+                custom_return_path: None,
             })
             .await;
         let data = match result.unwrap() {
@@ -54,7 +56,7 @@ mod domains_tests {
 
     #[tokio::test]
     async fn list_should_work() {
-        let mut server = mockito::Server::new();
+        let mut server = mockito::Server::new_async().await;
         let base_url = server.url();
 
         let list_domains_response = ListDomainsResponse {
@@ -91,7 +93,7 @@ mod domains_tests {
 
     #[tokio::test]
     async fn get_should_work() {
-        let mut server = mockito::Server::new();
+        let mut server = mockito::Server::new_async().await;
         let base_url = server.url();
 
         let get_domain_response = DomainDetails {
@@ -136,7 +138,7 @@ mod domains_tests {
 
     #[tokio::test]
     async fn delete_should_work() {
-        let mut server = mockito::Server::new();
+        let mut server = mockito::Server::new_async().await;
         let base_url = server.url();
 
         let delete_response = DeleteDomainResponse {
@@ -166,4 +168,273 @@ mod domains_tests {
 
         domains_mock.assert();
     }
+
+    fn fast_wait_options(timeout: Duration) -> WaitOptions {
+        WaitOptions {
+            initial_interval: Duration::from_millis(10),
+            max_interval: Duration::from_millis(20),
+            timeout,
+        }
+    }
+
+    #[tokio::test]
+    async fn wait_until_verified_should_poll_until_verified() {
+        let mut server = mockito::Server::new_async().await;
+        let base_url = server.url();
+
+        let pending_domain = DomainDetails {
+            object: "domain".into(),
+            id: "d91cd9bd-1176-453e-8fc1-35364d380206".into(),
+            name: "example.com".into(),
+            status: DomainStatus::Pending,
+            created_at: "2023-04-26T20:21:26.347412+00:00".into(),
+            region: "us-east-1".into(),
+            records: vec![
+                DnsRecord {
+                    record: EmailDnsRecord::Spf,
+                    name: "send".into(),
+                    r#type: DnsRecordType::Mx,
+                    ttl: "Auto".into(),
+                    status: DomainStatus::Verified,
+                    value: "feedback-smtp.us-east-1.amazonses.com".into(),
+                    priority: Some(10),
+                },
+                DnsRecord {
+                    record: EmailDnsRecord::Spf,
+                    name: "send".into(),
+                    r#type: DnsRecordType::Mx,
+                    ttl: "Auto".into(),
+                    status: DomainStatus::Pending,
+                    value: "feedback-smtp.us-east-1.amazonses.com".into(),
+                    priority: Some(10),
+                },
+            ],
+        };
+        let pending_mock = server
+            .mock("GET", "/domains/mock-id")
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(serde_json::to_string(&pending_domain).unwrap())
+            .expect(2)
+            .create();
+        let verified_domain = DomainDetails {
+            object: "domain".into(),
+            id: "d91cd9bd-1176-453e-8fc1-35364d380206".into(),
+            name: "example.com".into(),
+            status: DomainStatus::Verified,
+            created_at: "2023-04-26T20:21:26.347412+00:00".into(),
+            region: "us-east-1".into(),
+            records: vec![
+                DnsRecord {
+                    record: EmailDnsRecord::Spf,
+                    name: "send".into(),
+                    r#type: DnsRecordType::Mx,
+                    ttl: "Auto".into(),
+                    status: DomainStatus::Verified,
+                    value: "feedback-smtp.us-east-1.amazonses.com".into(),
+                    priority: Some(10),
+                },
+                DnsRecord {
+                    record: EmailDnsRecord::Spf,
+                    name: "send".into(),
+                    r#type: DnsRecordType::Mx,
+                    ttl: "Auto".into(),
+                    status: DomainStatus::Verified,
+                    value: "feedback-smtp.us-east-1.amazonses.com".into(),
+                    priority: Some(10),
+                },
+            ],
+        };
+        let verified_mock = server
+            .mock("GET", "/domains/mock-id")
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(serde_json::to_string(&verified_domain).unwrap())
+            .expect(1)
+            .create();
+
+        let mut client = Client::new("api-key");
+        client.domain_service.req_client.base_url = url::Url::parse(&base_url[..]).unwrap();
+        let mut progress: Vec<usize> = vec![];
+        let result = client
+            .domain_service
+            .wait_until_verified_with_progress(
+                "mock-id",
+                &fast_wait_options(Duration::from_secs(5)),
+                |records| {
+                    progress.push(
+                        records
+                            .iter()
+                            .filter(|record| record.status == DomainStatus::Verified)
+                            .count(),
+                    )
+                },
+            )
+            .await;
+
+        assert!(matches!(result.unwrap(), VerificationOutcome::Verified(_)));
+        assert_eq!(progress, vec![1, 1, 2]);
+        pending_mock.assert();
+        verified_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn wait_until_verified_should_report_failing_records() {
+        let mut server = mockito::Server::new_async().await;
+        let base_url = server.url();
+
+        let domain = DomainDetails {
+            object: "domain".into(),
+            id: "d91cd9bd-1176-453e-8fc1-35364d380206".into(),
+            name: "example.com".into(),
+            status: DomainStatus::Failed,
+            created_at: "2023-04-26T20:21:26.347412+00:00".into(),
+            region: "us-east-1".into(),
+            records: vec![
+                DnsRecord {
+                    record: EmailDnsRecord::Spf,
+                    name: "send".into(),
+                    r#type: DnsRecordType::Mx,
+                    ttl: "Auto".into(),
+                    status: DomainStatus::Verified,
+                    value: "feedback-smtp.us-east-1.amazonses.com".into(),
+                    priority: Some(10),
+                },
+                DnsRecord {
+                    record: EmailDnsRecord::Spf,
+                    name: "send".into(),
+                    r#type: DnsRecordType::Mx,
+                    ttl: "Auto".into(),
+                    status: DomainStatus::Failed,
+                    value: "feedback-smtp.us-east-1.amazonses.com".into(),
+                    priority: Some(10),
+                },
+            ],
+        };
+        let domains_mock = server
+            .mock("GET", "/domains/mock-id")
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(serde_json::to_string(&domain).unwrap())
+            .expect(1)
+            .create();
+
+        let mut client = Client::new("api-key");
+        client.domain_service.req_client.base_url = url::Url::parse(&base_url[..]).unwrap();
+        let result = client
+            .domain_service
+            .wait_until_verified("mock-id", &fast_wait_options(Duration::from_secs(5)))
+            .await;
+
+        match result.unwrap() {
+            VerificationOutcome::Failed {
+                failing_records, ..
+            } => {
+                assert_eq!(failing_records.len(), 1);
+                assert_eq!(failing_records[0].status, DomainStatus::Failed);
+            }
+            other => panic!("unexpected outcome {:?}", other),
+        }
+        domains_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn wait_until_verified_should_time_out() {
+        let mut server = mockito::Server::new_async().await;
+        let base_url = server.url();
+
+        let domain = DomainDetails {
+            object: "domain".into(),
+            id: "d91cd9bd-1176-453e-8fc1-35364d380206".into(),
+            name: "example.com".into(),
+            status: DomainStatus::Pending,
+            created_at: "2023-04-26T20:21:26.347412+00:00".into(),
+            region: "us-east-1".into(),
+            records: vec![DnsRecord {
+                record: EmailDnsRecord::Spf,
+                name: "send".into(),
+                r#type: DnsRecordType::Mx,
+                ttl: "Auto".into(),
+                status: DomainStatus::Pending,
+                value: "feedback-smtp.us-east-1.amazonses.com".into(),
+                priority: Some(10),
+            }],
+        };
+        let domains_mock = server
+            .mock("GET", "/domains/mock-id")
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(serde_json::to_string(&domain).unwrap())
+            .expect_at_least(2)
+            .create();
+
+        let mut client = Client::new("api-key");
+        client.domain_service.req_client.base_url = url::Url::parse(&base_url[..]).unwrap();
+        let result = client
+            .domain_service
+            .wait_until_verified("mock-id", &fast_wait_options(Duration::from_millis(50)))
+            .await;
+
+        assert!(matches!(result.unwrap(), VerificationOutcome::TimedOut(_)));
+        domains_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn create_with_builder_should_send_region() {
+        let mut server = mockito::Server::new_async().await;
+        let base_url = server.url();
+
+        let create_domain_response = CreateDomainResponse {
+            id: "mock-id".into(),
+            created_at: "2023-11-19T10:00:00.000Z".into(),
+            name: "domain.com".into(),
+            region: Region::EuWest1,
+            status: DomainStatus::Pending,
+            dnsProvider: "unknown".into(),
+        };
+
+        let domain_post_mock = server
+            .mock("POST", "/domains")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "name": "domain.com",
+                "region": "eu-west-1",
+                "custom_return_path": "outbound",
+            })))
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(serde_json::to_string(&create_domain_response).unwrap())
+            .create();
+
+        let mut client = Client::new("api-key");
+        client.domain_service.req_client.base_url = url::Url::parse(&base_url[..]).unwrap();
+        let request = CreateDomainRequest::builder("domain.com")
+            .region(Region::EuWest1)
+            .custom_return_path("outbound")
+            .build();
+        let result = client.domain_service.create(&request).await;
+        let data = match result.unwrap() {
+            resend_client_rs::reqlib::APIResponse::Success(data) => Some(data),
+            _ => None,
+        };
+        assert_eq!(data.unwrap().region, Region::EuWest1);
+
+        domain_post_mock.assert();
+    }
+
+    #[test]
+    fn region_should_round_trip() {
+        assert_eq!(
+            serde_json::from_str::<Region>("\"ap-northeast-1\"").unwrap(),
+            Region::ApNortheast1
+        );
+        assert_eq!(
+            serde_json::from_str::<Region>("\"eu-central-1\"").unwrap(),
+            Region::Other("eu-central-1".into())
+        );
+        assert_eq!(
+            serde_json::to_string(&Region::Other("eu-central-1".into())).unwrap(),
+            "\"eu-central-1\""
+        );
+        assert_eq!(Region::SaEast1.to_string(), "sa-east-1");
+    }
 }
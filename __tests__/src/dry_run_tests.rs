@@ -0,0 +1,175 @@
+#[cfg(test)]
+mod dry_run_tests {
+    use resend_client_rs::{
+        dry_run::DryRun,
+        emails::SendEmailRequest,
+        reqlib::{APIResponse, Error},
+        testing::FakeResend,
+        Client,
+    };
+
+    use crate::fixtures::send_email_request;
+
+    #[tokio::test]
+    async fn send_should_not_reach_the_api() {
+        let fake = FakeResend::new();
+        let mut client = fake.client();
+        client.enable_dry_run(DryRun::new());
+
+        let result = client
+            .email_service
+            .send(&SendEmailRequest {
+                to: vec!["customer@gmail.com".to_string()],
+                ..send_email_request()
+            })
+            .await;
+        let data = match result.unwrap() {
+            APIResponse::Success(data) => data,
+            other => panic!("unexpected result {:?}", other),
+        };
+
+        assert!(fake.requests().is_empty());
+        let emails = client.email_service.dry_run.as_ref().unwrap().emails();
+        assert_eq!(emails.len(), 1);
+        assert_eq!(emails[0].id, data.id);
+        assert_eq!(emails[0].payload["to"][0], "customer@gmail.com");
+    }
+
+    #[tokio::test]
+    async fn allowed_domains_should_pass_through() {
+        let fake = FakeResend::new();
+        let mut client = fake.client();
+        client.enable_dry_run(DryRun::new().allow_domain("qa.example.com"));
+
+        client
+            .email_service
+            .send(&SendEmailRequest {
+                to: vec!["Tester <tester@QA.example.com>".to_string()],
+                ..send_email_request()
+            })
+            .await
+            .unwrap();
+        // a single recipient outside of the allow-list keeps the whole email local
+        let mixed = SendEmailRequest {
+            to: vec!["tester@qa.example.com".to_string()],
+            bcc: Some(vec!["customer@gmail.com".to_string()]),
+            ..send_email_request()
+        };
+        client.email_service.send(&mixed).await.unwrap();
+
+        assert_eq!(fake.sent_emails().len(), 1);
+        assert_eq!(
+            client
+                .email_service
+                .dry_run
+                .as_ref()
+                .unwrap()
+                .emails()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn batch_should_only_send_allowed_emails() {
+        let fake = FakeResend::new();
+        let mut client = fake.client();
+        client.enable_dry_run(DryRun::new().allow_domain("qa.example.com"));
+
+        let batch = vec![
+            SendEmailRequest {
+                to: vec!["customer@gmail.com".to_string()],
+                ..send_email_request()
+            },
+            SendEmailRequest {
+                to: vec!["tester@qa.example.com".to_string()],
+                ..send_email_request()
+            },
+            SendEmailRequest {
+                to: vec!["other@gmail.com".to_string()],
+                ..send_email_request()
+            },
+        ];
+        let data = match client.email_service.send_batch(&batch).await.unwrap() {
+            APIResponse::Success(data) => data.data,
+            other => panic!("unexpected result {:?}", other),
+        };
+
+        let sent = fake.sent_emails();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(data.len(), 3);
+        assert_eq!(data[1].id, sent[0].id);
+        let recorded = client.email_service.dry_run.as_ref().unwrap().emails();
+        assert_eq!(recorded.len(), 2);
+        assert_eq!(data[0].id, recorded[0].id);
+        assert_eq!(data[2].id, recorded[1].id);
+    }
+
+    #[tokio::test]
+    async fn batch_should_fail_when_the_api_acknowledges_fewer_emails() {
+        let mut server = mockito::Server::new_async().await;
+        let batch_mock = server
+            .mock("POST", "/emails/batch")
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(r#"{"data":[{"id":"first-id"}]}"#)
+            .create();
+
+        let mut client = Client::new("api-key");
+        client.email_service.req_client.base_url = url::Url::parse(&server.url()).unwrap();
+        client.enable_dry_run(DryRun::new().allow_domain("qa.example.com"));
+        let emails = [
+            SendEmailRequest {
+                to: vec!["first@qa.example.com".to_string()],
+                ..send_email_request()
+            },
+            SendEmailRequest {
+                to: vec!["customer@gmail.com".to_string()],
+                ..send_email_request()
+            },
+            SendEmailRequest {
+                to: vec!["second@qa.example.com".to_string()],
+                ..send_email_request()
+            },
+        ];
+
+        match client.email_service.send_batch(&emails).await {
+            Err(APIResponse::Failure(Error::BatchMismatch { sent, acknowledged })) => {
+                assert_eq!((sent, acknowledged), (2, 1));
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+        batch_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn validation_should_run_in_dry_run() {
+        let fake = FakeResend::new();
+        let mut client = fake.client();
+        client.enable_dry_run(DryRun::new());
+
+        let invalid = SendEmailRequest {
+            to: vec![],
+            ..send_email_request()
+        };
+        match client.email_service.send(&invalid).await {
+            Err(APIResponse::Failure(Error::Validation(err))) => assert_eq!(err.field, "to"),
+            other => panic!("unexpected result {:?}", other),
+        }
+        match client
+            .email_service
+            .send_batch(&[send_email_request(), invalid])
+            .await
+        {
+            Err(APIResponse::Failure(Error::Validation(err))) => assert_eq!(err.field, "[1].to"),
+            other => panic!("unexpected result {:?}", other),
+        }
+        assert!(client
+            .email_service
+            .dry_run
+            .as_ref()
+            .unwrap()
+            .emails()
+            .is_empty());
+    }
+}
@@ -1,14 +1,20 @@
 #[cfg(test)]
-
 mod emails_tests {
     use resend_client_rs::{
-        emails::{Email, SendEmailRequest, SendEmailResponse},
+        emails::{Email, SendBatchResponse, SendEmailRequest, SendEmailResponse},
         Client,
     };
 
+    use crate::fixtures::send_email_request;
+
+    const CASSETTE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/cassettes/emails_integration.json"
+    );
+
     #[tokio::test]
     async fn send_should_work() {
-        let mut server = mockito::Server::new();
+        let mut server = mockito::Server::new_async().await;
         let base_url = server.url();
 
         let send_email_response = SendEmailResponse {
@@ -49,9 +55,64 @@ mod emails_tests {
         email_post_mock.assert();
     }
 
+    #[tokio::test]
+    async fn send_batch_should_work() {
+        let mut server = mockito::Server::new_async().await;
+        let base_url = server.url();
+
+        let send_batch_response = SendBatchResponse {
+            data: vec![
+                SendEmailResponse {
+                    id: "first-id".to_string(),
+                },
+                SendEmailResponse {
+                    id: "second-id".to_string(),
+                },
+            ],
+        };
+
+        let email_batch_mock = server
+            .mock("POST", "/emails/batch")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!([
+                { "to": ["first@domain.com"] },
+                { "to": ["second@domain.com"] },
+            ])))
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(serde_json::to_string(&send_batch_response).unwrap())
+            .create();
+
+        let mut client = Client::new("api-key");
+        client.email_service.req_client.base_url = url::Url::parse(&base_url[..]).unwrap();
+        let emails: Vec<SendEmailRequest> = ["first@domain.com", "second@domain.com"]
+            .iter()
+            .map(|to| SendEmailRequest {
+                subject: "My subject".to_string(),
+                from: "from@domain.com".to_string(),
+                to: vec![to.to_string()],
+                cc: None,
+                bcc: None,
+                reply_to: None,
+                html: None,
+                text: None,
+                tags: None,
+                attachments: None,
+                headers: None,
+            })
+            .collect();
+        let result = client.email_service.send_batch(&emails).await;
+        let data = match result.unwrap() {
+            resend_client_rs::reqlib::APIResponse::Success(data) => Some(data),
+            _ => None,
+        };
+        assert_eq!(data.unwrap().data[1].id, "second-id");
+
+        email_batch_mock.assert();
+    }
+
     #[tokio::test]
     async fn get_should_work() {
-        let mut server = mockito::Server::new();
+        let mut server = mockito::Server::new_async().await;
         let base_url = server.url();
 
         let email_details = Email {
@@ -94,37 +155,49 @@ mod emails_tests {
 
     #[tokio::test]
     async fn integration_test() {
-        // this test will only run if an resend api key is provided
+        // this test will only run if an resend api key is provided, setting RESEND_RECORD as
+        // well records the exchange into the cassette that `cassette_replay_test` uses
         let api_key = std::env::var("RESEND_API_KEY");
         let from_value = std::env::var("RESEND_FROM");
         let to_value = std::env::var("RESEND_TO");
 
-        if api_key.is_ok() && from_value.is_ok() && to_value.is_ok() {
-            let mut client = Client::new(api_key.unwrap());
+        if let (Ok(api_key), Ok(from_value), Ok(to_value)) = (api_key, from_value, to_value) {
+            let mut client = Client::new(api_key);
+            if std::env::var("RESEND_RECORD").is_ok() {
+                client.email_service.req_client.record_cassette(CASSETTE);
+            }
             // client.email_service.req_client.base_url = url::Url::parse("http://localhost:3000").unwrap();
             let result = client
                 .email_service
                 .send(&SendEmailRequest {
-                    subject: "My subject".to_string(),
-                    from: from_value.unwrap(),
-                    to: vec![to_value.unwrap()],
-                    cc: None,
-                    bcc: None,
-                    reply_to: None,
-                    html: None,
-                    text: Some("Hello World".to_string()),
-                    tags: None,
-                    attachments: None,
-                    headers: None,
+                    from: from_value,
+                    to: vec![to_value],
+                    ..send_email_request()
                 })
                 .await;
             let data = match result.unwrap() {
                 resend_client_rs::reqlib::APIResponse::Success(data) => Some(data),
                 _ => None,
             };
-            assert!(data.unwrap().id != "");
-        } else {
-            assert!(true);
+            assert!(!data.unwrap().id.is_empty());
         }
     }
+
+    #[tokio::test]
+    async fn cassette_replay_test() {
+        // the checked-in cassette is a hand-written fixture in the recorded format, so this
+        // covers the replay path and the fields written into it, not the live API
+        let mut client = Client::new("api-key");
+        client
+            .email_service
+            .req_client
+            .replay_cassette(CASSETTE)
+            .unwrap();
+        let result = client.email_service.send(&send_email_request()).await;
+        let data = match result.unwrap() {
+            resend_client_rs::reqlib::APIResponse::Success(data) => Some(data),
+            _ => None,
+        };
+        assert!(!data.unwrap().id.is_empty());
+    }
 }
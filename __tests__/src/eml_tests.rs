@@ -0,0 +1,310 @@
+#[cfg(test)]
+mod eml_tests {
+    use std::collections::HashMap;
+
+    use resend_client_rs::{
+        emails::{Attachment, SendEmailRequest},
+        eml::{from_eml, to_eml, to_eml_with_bcc},
+    };
+
+    use crate::fixtures::send_email_request;
+
+    // an email with non ascii headers and every header the eml writer knows about
+    fn localized_email() -> SendEmailRequest<'static> {
+        SendEmailRequest {
+            subject: "Grüße aus Köln".to_string(),
+            from: "Jürgen Müller <from@domain.com>".to_string(),
+            to: vec![
+                "to@domain.com".to_string(),
+                "Ann <ann@domain.com>".to_string(),
+            ],
+            cc: Some(vec!["cc@domain.com".to_string()]),
+            reply_to: Some("reply@domain.com".to_string()),
+            html: Some(r#"<p>Hallo <img src="cid:logo"></p>"#.to_string()),
+            text: Some("Hallo".to_string()),
+            headers: Some(HashMap::from([
+                ("Message-ID".to_string(), "<1@domain.com>".to_string()),
+                (
+                    "Date".to_string(),
+                    "Mon, 1 Jan 2024 10:00:00 +0000".to_string(),
+                ),
+                ("X-Campaign".to_string(), "spring".to_string()),
+            ])),
+            ..send_email_request()
+        }
+    }
+
+    fn to_string(email: &SendEmailRequest) -> String {
+        String::from_utf8(to_eml(email).unwrap()).unwrap()
+    }
+
+    // asserts that the lines appear in this order
+    fn assert_in_order(eml: &str, lines: &[&str]) {
+        let mut rest = eml;
+        for line in lines {
+            let index = rest
+                .find(line)
+                .unwrap_or_else(|| panic!("`{}` missing or out of order in\n{}", line, eml));
+            rest = &rest[index + line.len()..];
+        }
+    }
+
+    #[test]
+    fn to_eml_should_encode_headers() {
+        let eml = to_string(&localized_email());
+        assert_in_order(
+            &eml,
+            &[
+                "From: =?utf-8?B?SsO8cmdlbiBNw7xsbGVy?= <from@domain.com>\r\n",
+                "To: <to@domain.com>, \"Ann\" <ann@domain.com>\r\n",
+                "Subject: =?utf-8?B?R3LDvMOfZSBhdXMgS8O2bG4=?=\r\n",
+                "Cc: <cc@domain.com>\r\n",
+                "Reply-To: <reply@domain.com>\r\n",
+                "Date: Mon, 1 Jan 2024 10:00:00 +0000\r\n",
+                "Message-ID: <1@domain.com>\r\n",
+                "X-Campaign: spring\r\n",
+                "MIME-Version: 1.0\r\n",
+            ],
+        );
+        assert_eq!(eml.matches("Message-ID:").count(), 1);
+    }
+
+    #[test]
+    fn to_eml_should_keep_custom_message_id_and_date_in_any_case() {
+        let mut email = send_email_request();
+        email.headers = Some(HashMap::from([
+            ("message-id".to_string(), "<1@domain.com>".to_string()),
+            (
+                "DATE".to_string(),
+                "Mon, 1 Jan 2024 10:00:00 +0000".to_string(),
+            ),
+        ]));
+        let eml = to_string(&email);
+        assert_eq!(eml.to_ascii_lowercase().matches("message-id:").count(), 1);
+        assert_eq!(eml.to_ascii_lowercase().matches("\ndate:").count(), 1);
+        assert!(eml.contains("Message-ID: <1@domain.com>\r\n"));
+        assert!(eml.contains("Date: Mon, 1 Jan 2024 10:00:00 +0000\r\n"));
+    }
+
+    #[test]
+    fn to_eml_should_only_write_bcc_when_asked() {
+        let mut email = send_email_request();
+        email.bcc = Some(vec!["audit@domain.com".to_string()]);
+        assert!(!to_string(&email).contains("audit@domain.com"));
+        let eml = String::from_utf8(to_eml_with_bcc(&email).unwrap()).unwrap();
+        assert!(eml.contains("Bcc: <audit@domain.com>\r\n"));
+    }
+
+    #[test]
+    fn to_eml_should_nest_alternative_related_and_mixed_parts() {
+        let logo =
+            Attachment::new("logo.png", vec![0x89, b'P', b'N', b'G']).with_content_id("logo");
+        let invoice = Attachment::new("invoice.pdf", b"%PDF-1.4".to_vec());
+        let email = SendEmailRequest {
+            html: Some(r#"<p>Hallo <img src="cid:logo"></p>"#.to_string()),
+            text: Some("Hallo".to_string()),
+            attachments: Some(vec![&logo, &invoice]),
+            ..send_email_request()
+        };
+        let eml = to_string(&email);
+        assert_in_order(
+            &eml,
+            &[
+                "Content-Type: multipart/mixed;",
+                "Content-Type: multipart/alternative;",
+                "Content-Type: text/plain; charset=\"utf-8\"",
+                "\r\n\r\nHallo\r\n",
+                "Content-Type: multipart/related;",
+                "type=\"text/html\"",
+                "Content-Type: text/html; charset=\"utf-8\"",
+                r#"<p>Hallo <img src="cid:logo"></p>"#,
+                "Content-Type: image/png\r\nContent-Disposition: inline\r\nContent-ID: <logo>\r\nContent-Transfer-Encoding: base64\r\n\r\niVBORw==",
+                "Content-Type: application/pdf\r\nContent-Disposition: attachment; filename=\"invoice.pdf\"\r\nContent-Transfer-Encoding: base64\r\n\r\nJVBERi0xLjQ=",
+            ],
+        );
+    }
+
+    #[test]
+    fn to_eml_should_skip_multiparts_that_are_not_needed() {
+        let mut email = send_email_request();
+        let eml = to_string(&email);
+        assert!(eml.contains("Content-Type: text/plain; charset=\"utf-8\""));
+        assert!(!eml.contains("multipart/"));
+
+        // a content id only makes an attachment inline when there is html to show it in
+        let logo = Attachment::new("logo.png", vec![1, 2, 3]).with_content_id("logo");
+        email.attachments = Some(vec![&logo]);
+        let eml = to_string(&email);
+        assert!(eml.contains("multipart/mixed"));
+        assert!(!eml.contains("multipart/related"));
+        assert!(eml.contains("Content-Disposition: attachment; filename=\"logo.png\""));
+    }
+
+    #[test]
+    fn to_eml_should_use_the_content_type_of_attachments() {
+        let data = Attachment::new("data.bin", vec![1, 2, 3]);
+        let calendar =
+            Attachment::new("invite", "BEGIN:VCALENDAR").with_content_type("text/calendar");
+        let report = Attachment::new("Report.CSV", "a,b");
+        let email = SendEmailRequest {
+            attachments: Some(vec![&data, &calendar, &report]),
+            ..send_email_request()
+        };
+        let eml = to_string(&email);
+        assert!(eml.contains("Content-Type: application/octet-stream\r\n"));
+        assert!(eml.contains("Content-Type: text/calendar"));
+        assert!(eml.contains("Content-Type: text/csv"));
+    }
+
+    #[test]
+    fn to_eml_should_reject_attachments_without_content() {
+        let remote = Attachment::from_path("logo.png", "https://domain.com/logo.png");
+        let email = SendEmailRequest {
+            attachments: Some(vec![&remote]),
+            ..send_email_request()
+        };
+        let err = to_eml(&email).unwrap_err();
+        assert_eq!(err.field, "attachments");
+    }
+
+    const LEGACY: &str = "Return-Path: <bounce@legacy.domain.com>\r
+Received: from legacy.domain.com by mx.domain.com\r
+Received: from relay.domain.com by legacy.domain.com\r
+X-Received: by 2002:a05:6000 with SMTP id x1\r
+Delivered-To: ann@domain.com\r
+Authentication-Results: mx.domain.com; dkim=pass header.d=domain.com\r
+ARC-Seal: i=1; a=rsa-sha256; t=1704103200; cv=none\r
+ARC-Message-Signature: i=1; a=rsa-sha256; c=relaxed/relaxed\r
+DKIM-Signature: v=1; a=rsa-sha256; d=domain.com; s=s1\r
+From: =?iso-8859-1?Q?J=FCrgen_M=FCller?= <from@domain.com>\r
+To: Team: ann@domain.com, \"Doe, Bob\" <bob@domain.com>;\r
+Bcc: audit@domain.com\r
+Subject: =?iso-8859-1?Q?Gr=FC=DFe?=\r
+Date: Mon, 1 Jan 2024 10:00:00 +0000\r
+Message-ID: <legacy-1@domain.com>\r
+X-Mailer: LegacyMailer 2.1\r
+X-Campaign: =?utf-8?B?RnLDvGhsaW5n?=\r
+List-Unsubscribe: <mailto:unsubscribe@domain.com>\r
+MIME-Version: 1.0\r
+Content-Type: multipart/mixed; boundary=\"outer\"\r
+\r
+--outer\r
+Content-Type: multipart/alternative; boundary=\"inner\"\r
+\r
+--inner\r
+Content-Type: text/plain; charset=iso-8859-1\r
+Content-Transfer-Encoding: quoted-printable\r
+\r
+Gr=FC=DFe aus K=F6ln\r
+--inner\r
+Content-Type: text/html; charset=utf-8\r
+\r
+<p>Grüße</p>\r
+--inner--\r
+--outer\r
+Content-Type: application/pdf; name=\"invoice.pdf\"\r
+Content-Disposition: attachment; filename=\"invoice.pdf\"\r
+Content-Transfer-Encoding: base64\r
+\r
+JVBERi0xLjQ=\r
+--outer\r
+Content-Type: application/octet-stream\r
+Content-Transfer-Encoding: base64\r
+\r
+AQID\r
+--outer--\r
+";
+
+    #[test]
+    fn from_eml_should_read_a_legacy_message() {
+        let parsed = from_eml(LEGACY.as_bytes()).unwrap();
+        let email = parsed.request();
+        assert_eq!(email.from, "Jürgen Müller <from@domain.com>");
+        assert_eq!(
+            email.to,
+            vec!["ann@domain.com", "\"Doe, Bob\" <bob@domain.com>"]
+        );
+        assert_eq!(email.cc, None);
+        assert_eq!(email.bcc, Some(vec!["audit@domain.com".to_string()]));
+        assert_eq!(email.reply_to, None);
+        assert_eq!(email.subject, "Grüße");
+        assert_eq!(email.text.as_deref(), Some("Grüße aus Köln"));
+        assert_eq!(email.html.as_deref(), Some("<p>Grüße</p>"));
+
+        let mut headers: Vec<_> = email.headers.iter().flatten().collect();
+        headers.sort();
+        assert_eq!(
+            headers,
+            vec![
+                (
+                    &"List-Unsubscribe".to_string(),
+                    &"<mailto:unsubscribe@domain.com>".to_string()
+                ),
+                (&"X-Campaign".to_string(), &"Frühling".to_string()),
+            ]
+        );
+
+        assert!(email.validate().is_ok());
+        let attachments = email.attachments.unwrap();
+        assert_eq!(attachments.len(), 2);
+        assert_eq!(attachments[0].filename(), "invoice.pdf");
+        assert_eq!(attachments[0].content(), b"%PDF-1.4");
+        assert_eq!(attachments[0].content_type(), Some("application/pdf"));
+        assert_eq!(attachments[1].filename(), "attachment-2");
+        assert_eq!(attachments[1].content(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn from_eml_should_read_what_to_eml_writes() {
+        let logo =
+            Attachment::new("logo.png", vec![0x89, b'P', b'N', b'G']).with_content_id("logo");
+        let invoice = Attachment::new("invoice.pdf", b"%PDF-1.4".to_vec());
+        let mut original = SendEmailRequest {
+            attachments: Some(vec![&logo, &invoice]),
+            ..localized_email()
+        };
+        original
+            .to
+            .push("\"Doe, Bob\" <bob@domain.com>".to_string());
+
+        let parsed = from_eml(&to_eml(&original).unwrap()).unwrap();
+        let email = parsed.request();
+        assert_eq!(email.subject, original.subject);
+        assert_eq!(email.from, original.from);
+        assert_eq!(email.to, original.to);
+        assert_eq!(email.cc, original.cc);
+        assert_eq!(email.reply_to, original.reply_to);
+        assert_eq!(email.html, original.html);
+        assert_eq!(email.text, original.text);
+        assert_eq!(
+            email.headers,
+            Some(HashMap::from([(
+                "X-Campaign".to_string(),
+                "spring".to_string()
+            )]))
+        );
+        let attachments = email.attachments.unwrap();
+        assert_eq!(attachments[0].filename(), "attachment-1");
+        assert_eq!(attachments[0].content(), logo.content());
+        assert_eq!(attachments[0].content_id(), Some("logo"));
+        assert_eq!(attachments[0].content_type(), Some("image/png"));
+        assert_eq!(attachments[1].filename(), "invoice.pdf");
+        assert_eq!(attachments[1].content_id(), None);
+    }
+
+    #[test]
+    fn from_eml_should_reject_messages_without_sender() {
+        let err = from_eml(b"To: to@domain.com\r\nSubject: Hi\r\n\r\nHello").unwrap_err();
+        assert_eq!(err.field, "from");
+        let err = from_eml(b"").unwrap_err();
+        assert_eq!(err.field, "eml");
+    }
+
+    #[test]
+    fn from_eml_should_reject_repeated_custom_headers() {
+        let eml = "From: from@domain.com\r\nTo: to@domain.com\r\nX-Tag: a\r\nx-tag: b\r\n\r\nHello";
+        let err = from_eml(eml.as_bytes()).unwrap_err();
+        assert_eq!(err.field, "headers");
+        assert!(err.message.contains("x-tag"), "{}", err.message);
+    }
+}
@@ -0,0 +1,177 @@
+#[cfg(test)]
+mod export_tests {
+    use resend_client_rs::{
+        domains::{DnsRecord, DnsRecordType, DomainDetails, DomainStatus, EmailDnsRecord},
+        export::{
+            manifest, to_bind_zone, to_json, to_terraform_cloudflare, to_terraform_route53, to_yaml,
+        },
+    };
+
+    fn record(
+        record: EmailDnsRecord,
+        r#type: DnsRecordType,
+        name: &str,
+        value: &str,
+        priority: Option<u16>,
+    ) -> DnsRecord {
+        DnsRecord {
+            record,
+            r#type,
+            name: name.into(),
+            ttl: "Auto".into(),
+            status: DomainStatus::Pending,
+            value: value.into(),
+            priority,
+        }
+    }
+
+    fn domain(dkim: &str) -> DomainDetails {
+        DomainDetails {
+            object: "domain".into(),
+            id: "mock-id".into(),
+            name: "example.com".into(),
+            status: DomainStatus::Pending,
+            created_at: "2023-04-26T20:21:26.347412+00:00".into(),
+            region: "us-east-1".into(),
+            records: vec![
+                record(
+                    EmailDnsRecord::Spf,
+                    DnsRecordType::Mx,
+                    "send",
+                    "feedback-smtp.us-east-1.amazonses.com",
+                    Some(10),
+                ),
+                record(
+                    EmailDnsRecord::Spf,
+                    DnsRecordType::Txt,
+                    "send",
+                    "\"v=spf1 include:amazonses.com ~all\"",
+                    None,
+                ),
+                record(
+                    EmailDnsRecord::Dkim,
+                    DnsRecordType::Txt,
+                    "resend._domainkey",
+                    dkim,
+                    None,
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn bind_zone_should_work() {
+        let zone = to_bind_zone(&domain("p=MIGfMA0GCSqGSIb3"));
+        assert_eq!(
+            zone,
+            "; Resend records for example.com\n\
+             $ORIGIN example.com.\n\
+             send.example.com.\t3600\tIN\tMX\t10 feedback-smtp.us-east-1.amazonses.com.\n\
+             send.example.com.\t3600\tIN\tTXT\t\"v=spf1 include:amazonses.com ~all\"\n\
+             resend._domainkey.example.com.\t3600\tIN\tTXT\t\"p=MIGfMA0GCSqGSIb3\"\n"
+        );
+    }
+
+    #[test]
+    fn bind_zone_should_not_repeat_subdomain_labels() {
+        let mut domain = domain("p=MIGfMA0GCSqGSIb3");
+        domain.name = "mail.example.com".into();
+        domain.records[0].name = "send.mail".into();
+        domain.records[1].name = "send.mail".into();
+        domain.records[2].name = "resend._domainkey.mail".into();
+        let zone = to_bind_zone(&domain);
+        assert_eq!(
+            zone,
+            "; Resend records for mail.example.com\n\
+             $ORIGIN mail.example.com.\n\
+             send.mail.example.com.\t3600\tIN\tMX\t10 feedback-smtp.us-east-1.amazonses.com.\n\
+             send.mail.example.com.\t3600\tIN\tTXT\t\"v=spf1 include:amazonses.com ~all\"\n\
+             resend._domainkey.mail.example.com.\t3600\tIN\tTXT\t\"p=MIGfMA0GCSqGSIb3\"\n"
+        );
+        let terraform = to_terraform_cloudflare(&domain, "var.zone_id");
+        assert!(terraform.contains("name     = \"resend._domainkey.mail.example.com\"\n"));
+    }
+
+    #[test]
+    fn bind_zone_should_chunk_long_txt_values() {
+        let dkim = format!("p={}", "A".repeat(400));
+        let zone = to_bind_zone(&domain(&dkim));
+        let line = zone.lines().last().unwrap();
+        assert_eq!(
+            line,
+            format!(
+                "resend._domainkey.example.com.\t3600\tIN\tTXT\t\"p={}\" \"{}\"",
+                "A".repeat(253),
+                "A".repeat(147)
+            )
+        );
+    }
+
+    #[test]
+    fn terraform_cloudflare_should_work() {
+        let terraform = to_terraform_cloudflare(&domain("p=MIGfMA0GCSqGSIb3"), "var.zone_id");
+        assert_eq!(
+            terraform.split("\n\n").next().unwrap(),
+            "resource \"cloudflare_dns_record\" \"example_com_spf_mx_0\" {\n  \
+             zone_id  = var.zone_id\n  \
+             name     = \"send.example.com\"\n  \
+             type     = \"MX\"\n  \
+             content  = \"feedback-smtp.us-east-1.amazonses.com\"\n  \
+             ttl      = 1\n  \
+             priority = 10\n\
+             }"
+        );
+        assert!(terraform.contains("content  = \"v=spf1 include:amazonses.com ~all\"\n"));
+    }
+
+    #[test]
+    fn terraform_route53_should_chunk_long_txt_values() {
+        let dkim = format!("p={}", "A".repeat(400));
+        let terraform = to_terraform_route53(&domain(&dkim), "aws_route53_zone.main.zone_id");
+        assert!(terraform.contains("  records = [\"10 feedback-smtp.us-east-1.amazonses.com\"]\n"));
+        assert!(terraform.contains(&format!(
+            "  records = [\"p={}\\\"\\\"{}\"]\n",
+            "A".repeat(253),
+            "A".repeat(147)
+        )));
+        assert_eq!(
+            terraform.matches("resource \"aws_route53_record\"").count(),
+            3
+        );
+    }
+
+    #[test]
+    fn terraform_should_quote_zone_ids_and_fix_labels() {
+        let mut domain = domain("p=MIGfMA0GCSqGSIb3");
+        domain.name = "1password.com".into();
+        let terraform = to_terraform_cloudflare(&domain, "023e105f4ecef8ad9ca31a8372d0c353");
+        assert!(terraform.starts_with(
+            "resource \"cloudflare_dns_record\" \"_1password_com_spf_mx_0\" {\n  \
+             zone_id  = \"023e105f4ecef8ad9ca31a8372d0c353\"\n"
+        ));
+        let terraform = to_terraform_route53(&domain, "Z1\"${file(\"x\")}");
+        assert!(terraform.contains("  zone_id = \"Z1\\\"$${file(\\\"x\\\")}\"\n"));
+    }
+
+    #[test]
+    fn manifest_should_work() {
+        let domain = domain("p=MIGfMA0GCSqGSIb3");
+        let manifest = manifest(&domain);
+        assert_eq!(
+            manifest.records[1].value,
+            "v=spf1 include:amazonses.com ~all"
+        );
+        assert_eq!(
+            manifest.records[2].fully_qualified_name,
+            "resend._domainkey.example.com"
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&to_json(&domain)).unwrap();
+        assert_eq!(json["records"][0]["priority"], 10);
+        assert!(json["records"][0].get("ttl").is_none());
+
+        let yaml = to_yaml(&domain);
+        assert!(yaml.starts_with("domain: example.com\nregion: us-east-1\nrecords:\n"));
+        assert!(yaml.contains("  value: v=spf1 include:amazonses.com ~all\n"));
+    }
+}
@@ -0,0 +1,19 @@
+// requests shared by the test modules, tests change the fields they are about with
+// `SendEmailRequest { field, ..send_email_request() }`
+use resend_client_rs::emails::SendEmailRequest;
+
+pub fn send_email_request() -> SendEmailRequest<'static> {
+    SendEmailRequest {
+        subject: "My subject".to_string(),
+        from: "from@domain.com".to_string(),
+        to: vec!["to@domain.com".to_string()],
+        cc: None,
+        bcc: None,
+        reply_to: None,
+        html: None,
+        text: Some("Hello World".to_string()),
+        tags: None,
+        attachments: None,
+        headers: None,
+    }
+}
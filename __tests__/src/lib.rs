@@ -1,2 +1,27 @@
-mod emails_tests;
+#![allow(clippy::module_inception)]
+
+mod blocking_tests;
+mod cassette_tests;
+mod css_tests;
+mod diagnostics_tests;
 mod domains_tests;
+mod dry_run_tests;
+mod emails_tests;
+mod eml_tests;
+mod export_tests;
+#[cfg(test)]
+mod fixtures;
+mod markdown_tests;
+mod merge_tests;
+mod middleware_tests;
+mod mjml_tests;
+mod otel_tests;
+mod plain_text_tests;
+mod redirect_tests;
+mod reqlib_tests;
+mod secret_tests;
+mod smtp_tests;
+mod templates_tests;
+mod testing_tests;
+mod tracing_tests;
+mod transport_tests;
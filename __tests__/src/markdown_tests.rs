@@ -0,0 +1,85 @@
+#[cfg(test)]
+mod markdown_tests {
+    use resend_client_rs::{emails::EmailBody, markdown::Markdown, testing::FakeResend};
+
+    use crate::fixtures::send_email_request;
+
+    const SOURCE: &str = "# Deploy finished
+
+Build **42** of [api](https://ci.domain.com/42) is live.
+
+| Service | Status |
+| ------- | ------ |
+| api     | up     |
+| worker  | down   |
+
+- logs are kept for 7 days
+- ping `#ops` on failures
+";
+
+    #[test]
+    fn content_html_should_render_commonmark_with_tables() {
+        let html = Markdown::new(SOURCE).content_html();
+        assert!(html.starts_with("<h1>Deploy finished</h1>"));
+        assert!(
+            html.contains(r#"<strong>42</strong> of <a href="https://ci.domain.com/42">api</a>"#)
+        );
+        assert!(html.contains("<th>Service</th>"));
+        assert!(html.contains("<td>worker</td>"));
+        assert!(html.contains("<code>#ops</code>"));
+    }
+
+    #[test]
+    fn to_text_should_produce_a_clean_text_version() {
+        assert_eq!(
+            Markdown::new(SOURCE).to_text(),
+            "Deploy finished\n\nBuild 42 of api (https://ci.domain.com/42) is live.\n\nService | Status\napi | up\nworker | down\n\n- logs are kept for 7 days\n- ping #ops on failures"
+        );
+    }
+
+    #[test]
+    fn to_html_should_wrap_the_content_in_a_layout() {
+        let markdown = Markdown::new("Hi *there*");
+        let default = markdown.to_html().unwrap();
+        assert!(default.starts_with("<!DOCTYPE html>"));
+        assert!(default.contains("<p>Hi <em>there</em></p>"));
+
+        let custom = markdown
+            .with_layout("<main>{{content}}</main><footer>Acme</footer>")
+            .to_html()
+            .unwrap();
+        assert_eq!(
+            custom,
+            "<main><p>Hi <em>there</em></p>\n</main><footer>Acme</footer>"
+        );
+    }
+
+    #[test]
+    fn to_html_should_reject_a_layout_without_placeholder() {
+        let err = Markdown::new("Hi")
+            .with_layout("<main></main>")
+            .to_html()
+            .unwrap_err();
+        assert_eq!(err.field, "html");
+    }
+
+    #[tokio::test]
+    async fn set_body_should_send_html_and_text() {
+        let fake = FakeResend::new();
+        let client = fake.client();
+        let mut email = send_email_request();
+        email
+            .set_body(EmailBody::Markdown(
+                Markdown::new("Hello **World**").with_layout("<div>{{content}}</div>"),
+            ))
+            .unwrap();
+        client.email_service.send(&email).await.unwrap();
+
+        let sent = fake.sent_emails();
+        assert_eq!(
+            sent[0].html.as_deref(),
+            Some("<div><p>Hello <strong>World</strong></p>\n</div>")
+        );
+        assert_eq!(sent[0].text.as_deref(), Some("Hello World"));
+    }
+}
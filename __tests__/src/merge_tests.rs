@@ -0,0 +1,277 @@
+#[cfg(test)]
+mod merge_tests {
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use http::{Method, StatusCode};
+    use serde::Serialize;
+
+    use resend_client_rs::{
+        emails::{SendEmailRequest, SendInterceptor},
+        merge::{MailMerge, MergeError, Recipient},
+        redirect::RecipientRedirect,
+        reqlib::{APIResponse, ValidationError},
+        testing::FakeResend,
+    };
+
+    struct Footer(Arc<AtomicUsize>);
+
+    impl SendInterceptor for Footer {
+        fn intercept(&self, email: &mut SendEmailRequest<'_>) -> Result<(), ValidationError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            if let Some(text) = email.text.as_mut() {
+                text.push_str("\n--\nfooter");
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Customer {
+        name: String,
+        plan: Plan,
+    }
+
+    #[derive(Serialize)]
+    struct Plan {
+        name: &'static str,
+        seats: u32,
+    }
+
+    fn template() -> SendEmailRequest<'static> {
+        SendEmailRequest {
+            subject: "Welcome {{ name }}".to_string(),
+            from: "from@domain.com".to_string(),
+            to: vec![],
+            cc: None,
+            bcc: None,
+            reply_to: None,
+            html: Some(
+                "<p>Hi {{name}}, you have {{plan.seats}} {{plan.name}} seats</p>".to_string(),
+            ),
+            text: Some("Hi {{name}}".to_string()),
+            tags: None,
+            attachments: None,
+            headers: None,
+        }
+    }
+
+    fn customer(name: &str) -> Customer {
+        Customer {
+            name: name.to_string(),
+            plan: Plan {
+                name: "Pro",
+                seats: 5,
+            },
+        }
+    }
+
+    #[test]
+    fn placeholders_should_list_each_name_once() {
+        let merge = MailMerge::new(template());
+        assert_eq!(
+            merge.placeholders().unwrap(),
+            vec!["name", "plan.seats", "plan.name"]
+        );
+    }
+
+    #[test]
+    fn expand_should_fill_placeholders_per_recipient() {
+        let merge = MailMerge::new(template());
+        let emails = merge
+            .expand(vec![
+                Recipient::new("a@domain.com", customer("Ann")),
+                Recipient::new("b@domain.com", customer("<Bob & co>")),
+            ])
+            .unwrap();
+        assert_eq!(emails.len(), 2);
+        assert_eq!(emails[0].to, vec!["a@domain.com"]);
+        assert_eq!(emails[0].subject, "Welcome Ann");
+        assert_eq!(
+            emails[0].html.as_deref(),
+            Some("<p>Hi Ann, you have 5 Pro seats</p>")
+        );
+        assert_eq!(emails[1].subject, "Welcome <Bob & co>");
+        assert_eq!(emails[1].text.as_deref(), Some("Hi <Bob & co>"));
+        assert_eq!(
+            emails[1].html.as_deref(),
+            Some("<p>Hi &lt;Bob &amp; co&gt;, you have 5 Pro seats</p>")
+        );
+    }
+
+    #[test]
+    fn expand_should_drop_template_cc_and_bcc() {
+        let mut template = template();
+        template.cc = Some(vec!["cc@domain.com".to_string()]);
+        template.bcc = Some(vec!["bcc@domain.com".to_string()]);
+        let emails = MailMerge::new(template)
+            .expand([Recipient::new("a@domain.com", customer("Ann"))])
+            .unwrap();
+        assert_eq!(emails[0].cc, None);
+        assert_eq!(emails[0].bcc, None);
+    }
+
+    #[test]
+    fn expand_should_accept_csv_like_rows() {
+        let mut template = template();
+        template.html = None;
+        let row: HashMap<String, String> = [("name".to_string(), "Ann".to_string())].into();
+        let emails = MailMerge::new(template)
+            .expand([Recipient::new("a@domain.com", row)])
+            .unwrap();
+        assert_eq!(emails[0].subject, "Welcome Ann");
+    }
+
+    #[test]
+    fn expand_should_report_unresolved_placeholders() {
+        let err = MailMerge::new(template())
+            .expand([
+                Recipient::new("a@domain.com", HashMap::from([("name", "Ann")])),
+                Recipient::new("b@domain.com", HashMap::from([("name", "Bob")])),
+            ])
+            .unwrap_err();
+        assert_eq!(err.field, "[0].html");
+        assert_eq!(err.message, "`{{plan.seats}}` has no value");
+    }
+
+    #[test]
+    fn expand_should_reject_malformed_placeholders() {
+        let mut unclosed = template();
+        unclosed.subject = "Hi {{name".to_string();
+        let err = MailMerge::new(unclosed).placeholders().unwrap_err();
+        assert_eq!(err.field, "subject");
+
+        let mut invalid = template();
+        invalid.text = Some("Hi {{ first name }}".to_string());
+        let err = MailMerge::new(invalid)
+            .expand([Recipient::new("a@domain.com", customer("Ann"))])
+            .unwrap_err();
+        assert_eq!(err.field, "text");
+    }
+
+    #[tokio::test]
+    async fn send_merge_should_send_in_batches_and_report_per_recipient() {
+        let fake = FakeResend::new();
+        let client = fake.client();
+        fake.fail_next(
+            Method::POST,
+            "emails/batch",
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too many requests",
+        );
+        let recipients =
+            (0..250).map(|i| Recipient::new(format!("user{}@domain.com", i), customer("Ann")));
+
+        let report = client
+            .email_service
+            .send_merge(&MailMerge::new(template()), recipients)
+            .await
+            .unwrap();
+
+        // the first batch failed, the other two still went out
+        let batches = fake
+            .requests()
+            .iter()
+            .filter(|request| request.url.path() == "/emails/batch")
+            .count();
+        assert_eq!(batches, 3);
+        assert_eq!(fake.sent_emails().len(), 150);
+        assert!(!report.is_ok());
+        assert_eq!(report.results.len(), 250);
+        assert_eq!(report.failed().count(), 100);
+        assert_eq!(report.sent().count(), 150);
+        assert_eq!(report.results[0].to, "user0@domain.com");
+        match report.results[99].result.as_ref().unwrap_err() {
+            MergeError::Batch(response) => match response.as_ref() {
+                APIResponse::Error(err) => assert_eq!(err.status_code, 429),
+                other => panic!("unexpected response {:?}", other),
+            },
+            other => panic!("unexpected response {:?}", other),
+        }
+        let sent = &report.results[249];
+        assert_eq!(sent.to, "user249@domain.com");
+        let email = fake
+            .sent_emails()
+            .into_iter()
+            .find(|email| &email.id == sent.result.as_ref().unwrap())
+            .unwrap();
+        assert_eq!(email.to, vec!["user249@domain.com"]);
+        assert_eq!(email.subject, "Welcome Ann");
+    }
+
+    #[tokio::test]
+    async fn send_merge_should_not_send_anything_when_an_email_is_invalid() {
+        let fake = FakeResend::new();
+        let client = fake.client();
+        let recipients = (0..150).map(|i| match i {
+            // pushes the email past the 40MB size limit
+            120 => Recipient::new(
+                "user120@domain.com",
+                customer(&"a".repeat(40 * 1024 * 1024)),
+            ),
+            _ => Recipient::new(format!("user{}@domain.com", i), customer("Ann")),
+        });
+
+        let err = client
+            .email_service
+            .send_merge(&MailMerge::new(template()), recipients)
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.field, "[120].size");
+        assert!(fake.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn send_merge_should_run_interceptors_once() {
+        let fake = FakeResend::new();
+        let mut client = fake.client();
+        let calls = Arc::new(AtomicUsize::new(0));
+        client.email_service.add_interceptor(Footer(calls.clone()));
+
+        let report = client
+            .email_service
+            .send_merge(
+                &MailMerge::new(template()),
+                [Recipient::new("a@domain.com", customer("Ann"))],
+            )
+            .await
+            .unwrap();
+
+        assert!(report.is_ok());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            fake.sent_emails()[0].text.as_deref(),
+            Some("Hi Ann\n--\nfooter")
+        );
+    }
+
+    #[tokio::test]
+    async fn send_merge_should_report_the_merged_recipients_when_redirected() {
+        let fake = FakeResend::new();
+        let mut client = fake.client();
+        client
+            .email_service
+            .add_interceptor(RecipientRedirect::new("inbox@qa.example.com"));
+
+        let report = client
+            .email_service
+            .send_merge(
+                &MailMerge::new(template()),
+                [
+                    Recipient::new("a@domain.com", customer("Ann")),
+                    Recipient::new("b@domain.com", customer("Bob")),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let to: Vec<_> = report.results.iter().map(|result| &result.to).collect();
+        assert_eq!(to, vec!["a@domain.com", "b@domain.com"]);
+        assert!(fake
+            .sent_emails()
+            .iter()
+            .all(|email| email.to == vec!["inbox@qa.example.com"]));
+    }
+}
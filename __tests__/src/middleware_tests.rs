@@ -0,0 +1,156 @@
+#[cfg(test)]
+mod middleware_tests {
+    use std::sync::{Arc, Mutex};
+
+    use http::{header::HeaderName, HeaderMap, HeaderValue, StatusCode};
+    use resend_client_rs::{
+        middleware::{DefaultHeaders, Logging, Middleware, Next},
+        reqlib::APIResponse,
+        testing::FakeResend,
+        transport::{TransportFuture, TransportRequest, TransportResponse},
+    };
+
+    use crate::fixtures::send_email_request;
+
+    struct DefaultTags;
+
+    impl Middleware for DefaultTags {
+        fn handle<'a>(
+            &'a self,
+            mut request: TransportRequest,
+            next: Next<'a>,
+        ) -> TransportFuture<'a> {
+            if request.path() == "/emails" {
+                if let Some(mut body) = request.json_body() {
+                    body["tags"] = serde_json::json!([{ "name": "env", "value": "staging" }]);
+                    request.set_json_body(&body);
+                }
+            }
+            next.run(request)
+        }
+    }
+
+    struct Recorder {
+        name: &'static str,
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Middleware for Recorder {
+        fn handle<'a>(&'a self, request: TransportRequest, next: Next<'a>) -> TransportFuture<'a> {
+            Box::pin(async move {
+                self.calls
+                    .lock()
+                    .unwrap()
+                    .push(format!("before {}", self.name));
+                let result = next.run(request).await;
+                self.calls
+                    .lock()
+                    .unwrap()
+                    .push(format!("after {}", self.name));
+                result
+            })
+        }
+    }
+
+    struct Maintenance;
+
+    impl Middleware for Maintenance {
+        fn handle<'a>(
+            &'a self,
+            _request: TransportRequest,
+            _next: Next<'a>,
+        ) -> TransportFuture<'a> {
+            Box::pin(async move {
+                Ok(TransportResponse {
+                    status: StatusCode::SERVICE_UNAVAILABLE,
+                    headers: HeaderMap::new(),
+                    body: br#"{"name":"maintenance","status_code":503,"message":"Try later"}"#
+                        .to_vec(),
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn default_headers_should_be_added() {
+        let fake = FakeResend::new();
+        let mut client = fake.client();
+        client.add_middleware(
+            DefaultHeaders::new()
+                .header(
+                    HeaderName::from_static("x-correlation-id"),
+                    HeaderValue::from_static("abc-123"),
+                )
+                .header(
+                    http::header::USER_AGENT,
+                    HeaderValue::from_static("overridden"),
+                ),
+        );
+        client.add_middleware(Logging::new().with_bodies());
+
+        client
+            .email_service
+            .send(&send_email_request())
+            .await
+            .unwrap();
+        client.domain_service.list().await.unwrap();
+
+        let requests = fake.requests();
+        assert_eq!(requests.len(), 2);
+        for request in requests.iter() {
+            assert_eq!(request.headers["x-correlation-id"], "abc-123");
+            // headers set by the client itself win
+            assert_eq!(request.headers["user-agent"], "resend-rust/0.1.0");
+        }
+    }
+
+    #[tokio::test]
+    async fn middleware_should_mutate_the_body() {
+        let fake = FakeResend::new();
+        let mut client = fake.client();
+        client.email_service.req_client.add_middleware(DefaultTags);
+
+        client
+            .email_service
+            .send(&send_email_request())
+            .await
+            .unwrap();
+
+        let sent = &fake.sent_emails()[0];
+        assert_eq!(sent.raw["tags"][0]["name"], "env");
+        assert_eq!(sent.raw["tags"][0]["value"], "staging");
+    }
+
+    #[tokio::test]
+    async fn middleware_should_run_in_order() {
+        let fake = FakeResend::new();
+        let mut client = fake.client();
+        let calls = Arc::new(Mutex::new(vec![]));
+        for name in ["outer", "inner"] {
+            client.domain_service.req_client.add_middleware(Recorder {
+                name,
+                calls: calls.clone(),
+            });
+        }
+
+        client.domain_service.list().await.unwrap();
+
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec!["before outer", "before inner", "after inner", "after outer"]
+        );
+    }
+
+    #[tokio::test]
+    async fn middleware_should_short_circuit() {
+        let fake = FakeResend::new();
+        let mut client = fake.client();
+        client.add_middleware(Maintenance);
+
+        match client.email_service.send(&send_email_request()).await {
+            Err(APIResponse::Error(err)) => assert_eq!(err.name, "maintenance"),
+            other => panic!("unexpected result {:?}", other),
+        }
+        assert!(fake.requests().is_empty());
+    }
+}
@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod mjml_tests {
+    use resend_client_rs::{
+        emails::{BodyError, EmailBody},
+        mjml::mjml_to_html,
+        testing::FakeResend,
+    };
+
+    use crate::fixtures::send_email_request;
+
+    const MJML: &str = r#"<mjml>
+        <mj-head><mj-title>Welcome</mj-title></mj-head>
+        <mj-body>
+            <mj-section>
+                <mj-column><mj-text>Hello World</mj-text></mj-column>
+                <mj-column><mj-button href="https://resend.com">Open</mj-button></mj-column>
+            </mj-section>
+        </mj-body>
+    </mjml>"#;
+
+    #[test]
+    fn mjml_to_html_should_render_responsive_html() {
+        let html = mjml_to_html(MJML).unwrap();
+        assert!(html.starts_with("<!doctype html>"));
+        assert!(html.contains("<title>Welcome</title>"));
+        assert!(html.contains("Hello World"));
+        assert!(html.contains(r#"href="https://resend.com""#));
+        assert!(html.contains("@media only screen and (min-width:480px)"));
+        assert!(!html.contains("<mj-"));
+    }
+
+    #[test]
+    fn mjml_to_html_should_report_invalid_templates() {
+        for source in [
+            "<mjml><mj-body><mj-text>Hi</mj-body></mjml>",
+            "<p>Not MJML</p>",
+        ] {
+            let err = mjml_to_html(source).unwrap_err();
+            assert!(!err.message.is_empty());
+            assert!(err.to_string().starts_with("invalid MJML: "), "{}", err);
+        }
+    }
+
+    #[test]
+    fn mjml_to_html_should_report_where_parsing_failed() {
+        let cases = [
+            (
+                "<mjml><mj-head><mj-text>x</mj-text></mj-head></mjml>",
+                16..23,
+            ),
+            (
+                "<mjml>\n<mj-body><mj-text foo=>Hi</mj-text></mj-body></mjml>",
+                24..24,
+            ),
+            ("<mjml><mj-body><mj-text>Hi</mj-body></mjml>", 43..43),
+        ];
+        for (source, expected) in cases {
+            let err = mjml_to_html(source).unwrap_err();
+            assert_eq!(err.position, Some(expected), "{}", err);
+        }
+    }
+
+    #[test]
+    fn set_body_should_fill_html_and_text() {
+        let mut email = send_email_request();
+        email.set_body(EmailBody::Text("Hi".to_string())).unwrap();
+        email
+            .set_body(EmailBody::Html("<p>Hi</p>".to_string()))
+            .unwrap();
+        assert_eq!(email.text.as_deref(), Some("Hi"));
+        assert_eq!(email.html.as_deref(), Some("<p>Hi</p>"));
+    }
+
+    #[tokio::test]
+    async fn set_body_should_send_compiled_mjml() {
+        let fake = FakeResend::new();
+        let client = fake.client();
+        let mut email = send_email_request();
+        email.set_body(EmailBody::Mjml(MJML.to_string())).unwrap();
+        client.email_service.send(&email).await.unwrap();
+
+        let err = email
+            .set_body(EmailBody::Mjml("<mjml><mj-body>".to_string()))
+            .unwrap_err();
+        match err {
+            BodyError::Mjml(err) => assert!(err.to_string().starts_with("invalid MJML: ")),
+            other => panic!("unexpected error {:?}", other),
+        }
+
+        let sent = fake.sent_emails();
+        assert_eq!(sent.len(), 1);
+        assert!(sent[0].html.as_deref().unwrap().contains("Hello World"));
+    }
+}
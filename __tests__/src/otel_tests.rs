@@ -0,0 +1,198 @@
+#[cfg(test)]
+mod otel_tests {
+    use http::{Method, StatusCode};
+    use opentelemetry::metrics::MeterProvider;
+    use opentelemetry_sdk::metrics::data::{AggregatedMetrics, MetricData, ResourceMetrics};
+    use opentelemetry_sdk::metrics::{InMemoryMetricExporter, PeriodicReader, SdkMeterProvider};
+    use resend_client_rs::{emails::SendEmailRequest, testing::FakeResend, Client};
+
+    use crate::fixtures::send_email_request;
+
+    fn client_with_metrics(
+        fake: &std::sync::Arc<FakeResend>,
+    ) -> (Client, SdkMeterProvider, InMemoryMetricExporter) {
+        let exporter = InMemoryMetricExporter::default();
+        let provider = SdkMeterProvider::builder()
+            .with_reader(PeriodicReader::builder(exporter.clone()).build())
+            .build();
+        let mut client = fake.client();
+        client.enable_metrics(&provider.meter("resend"));
+        (client, provider, exporter)
+    }
+
+    fn collect(
+        provider: &SdkMeterProvider,
+        exporter: &InMemoryMetricExporter,
+    ) -> Vec<ResourceMetrics> {
+        provider.force_flush().unwrap();
+        exporter.get_finished_metrics().unwrap()
+    }
+
+    // `(attributes, value)` of every data point of the named metric, attributes sorted by key
+    fn points(metrics: &[ResourceMetrics], name: &str) -> Vec<(Vec<String>, f64)> {
+        let mut points = vec![];
+        for metric in metrics
+            .iter()
+            .flat_map(|resource| resource.scope_metrics())
+            .flat_map(|scope| scope.metrics())
+            .filter(|metric| metric.name() == name)
+        {
+            macro_rules! collect_points {
+                ($data:expr) => {
+                    match $data {
+                        MetricData::Sum(sum) => sum
+                            .data_points()
+                            .map(|p| {
+                                (
+                                    p.attributes().cloned().collect::<Vec<_>>(),
+                                    p.value() as f64,
+                                )
+                            })
+                            .collect::<Vec<_>>(),
+                        MetricData::Gauge(gauge) => gauge
+                            .data_points()
+                            .map(|p| {
+                                (
+                                    p.attributes().cloned().collect::<Vec<_>>(),
+                                    p.value() as f64,
+                                )
+                            })
+                            .collect::<Vec<_>>(),
+                        MetricData::Histogram(histogram) => histogram
+                            .data_points()
+                            .map(|p| {
+                                (
+                                    p.attributes().cloned().collect::<Vec<_>>(),
+                                    p.count() as f64,
+                                )
+                            })
+                            .collect::<Vec<_>>(),
+                        _ => vec![],
+                    }
+                };
+            }
+            let data = match metric.data() {
+                AggregatedMetrics::F64(data) => collect_points!(data),
+                AggregatedMetrics::U64(data) => collect_points!(data),
+                AggregatedMetrics::I64(data) => collect_points!(data),
+            };
+            for (attributes, value) in data {
+                let mut attributes: Vec<String> = attributes
+                    .iter()
+                    .map(|kv| format!("{}={}", kv.key, kv.value))
+                    .collect();
+                attributes.sort();
+                points.push((attributes, value));
+            }
+        }
+        points.sort_by(|a, b| a.0.cmp(&b.0));
+        points
+    }
+
+    fn attributes(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn send_should_count_requests_emails_and_recipients() {
+        let fake = FakeResend::new();
+        let (client, provider, exporter) = client_with_metrics(&fake);
+        let email = SendEmailRequest {
+            cc: Some(vec!["cc@domain.com".to_string()]),
+            ..send_email_request()
+        };
+        client.email_service.send(&email).await.unwrap();
+        client
+            .email_service
+            .send_batch(&[email.clone(), email])
+            .await
+            .unwrap();
+
+        let metrics = collect(&provider, &exporter);
+        assert_eq!(
+            points(&metrics, "resend.client.requests"),
+            vec![
+                (
+                    attributes(&["endpoint=/emails", "http.method=POST", "outcome=success"]),
+                    1.0
+                ),
+                (
+                    attributes(&[
+                        "endpoint=/emails/batch",
+                        "http.method=POST",
+                        "outcome=success"
+                    ]),
+                    1.0
+                ),
+            ]
+        );
+        assert_eq!(
+            points(&metrics, "resend.client.emails.sent"),
+            vec![(vec![], 3.0)]
+        );
+        assert_eq!(
+            points(&metrics, "resend.client.emails.recipients"),
+            vec![(vec![], 6.0)]
+        );
+        assert_eq!(
+            points(&metrics, "resend.client.request.duration"),
+            vec![
+                (attributes(&["endpoint=/emails", "http.method=POST"]), 1.0),
+                (
+                    attributes(&["endpoint=/emails/batch", "http.method=POST"]),
+                    1.0
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn requests_should_be_counted_by_outcome_with_ids_removed() {
+        let fake = FakeResend::new();
+        let (client, provider, exporter) = client_with_metrics(&fake);
+        fake.fail_next(
+            Method::GET,
+            "domains/abc",
+            StatusCode::NOT_FOUND,
+            "not found",
+        );
+        let _ = client.domain_service.get("abc").await;
+        let _ = client.domain_service.get("def").await;
+        let invalid = SendEmailRequest {
+            to: vec![],
+            ..send_email_request()
+        };
+        let _ = client.email_service.send(&invalid).await;
+
+        let metrics = collect(&provider, &exporter);
+        assert_eq!(
+            points(&metrics, "resend.client.requests"),
+            vec![
+                (
+                    attributes(&["endpoint=/domains/{id}", "http.method=GET", "outcome=error"]),
+                    2.0
+                ),
+                (
+                    attributes(&["endpoint=/emails", "http.method=POST", "outcome=validation"]),
+                    1.0
+                ),
+            ]
+        );
+        assert!(points(&metrics, "resend.client.emails.sent").is_empty());
+    }
+
+    #[tokio::test]
+    async fn rate_limit_remaining_should_be_recorded() {
+        let fake = FakeResend::new();
+        let (client, provider, exporter) = client_with_metrics(&fake);
+        fake.set_rate_limit(5);
+        client.email_service.list().await.unwrap();
+        client.email_service.list().await.unwrap();
+
+        let metrics = collect(&provider, &exporter);
+        assert_eq!(
+            points(&metrics, "resend.client.rate_limit.remaining"),
+            vec![(attributes(&["endpoint=/emails"]), 3.0)]
+        );
+    }
+}
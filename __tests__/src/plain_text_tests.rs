@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod plain_text_tests {
+    use resend_client_rs::{
+        emails::SendEmailRequest,
+        plain_text::{html_to_text, TextAlternative},
+        testing::FakeResend,
+    };
+
+    use crate::fixtures::send_email_request;
+
+    #[test]
+    fn html_to_text_should_keep_links() {
+        assert_eq!(
+            html_to_text(
+                r#"<p>Read the <a href="https://resend.com/docs?a=1&amp;b=2">docs</a>, mail <a href="mailto:hi@domain.com">hi@domain.com</a> or <a href='#top'>go up</a>.</p><a href="https://resend.com"></a>"#
+            ),
+            "Read the docs (https://resend.com/docs?a=1&b=2), mail hi@domain.com or go up.\n\nhttps://resend.com"
+        );
+    }
+
+    #[test]
+    fn html_to_text_should_turn_lists_into_bullets() {
+        let html = "<p>Steps:</p><ol start=\"3\"><li>Create a domain</li><li>Verify it<ul><li>MX</li><li>TXT</li></ul></li></ol><ul>\n  <li>  spaced   out </li></ul><p>Done</p>";
+        assert_eq!(
+            html_to_text(html),
+            "Steps:\n\n3. Create a domain\n4. Verify it\n  - MX\n  - TXT\n\n- spaced out\n\nDone"
+        );
+    }
+
+    #[test]
+    fn html_to_text_should_keep_table_rows_readable() {
+        let html = r#"<table>
+            <tr><th>Plan</th><th>Price</th></tr>
+            <tr><td>Free</td><td> $0 </td></tr>
+            <tr><td>Pro <img src="star.png" alt="(popular)"></td><td>$20</td></tr>
+        </table><p>Prices per month</p>"#;
+        assert_eq!(
+            html_to_text(html),
+            "Plan | Price\nFree | $0\nPro (popular) | $20\n\nPrices per month"
+        );
+    }
+
+    #[test]
+    fn html_to_text_should_drop_style_script_and_head() {
+        let html = r#"<!DOCTYPE html><html><head><title>Ignored</title><style>p > a { color: red }</style></head>
+            <body><!-- a <p>comment</p> --><SCRIPT type="text/javascript">if (a < b) { alert("x") }</SCRIPT>
+            <div>Visible&nbsp;text &copy; 2024 &#8212; &#x2713; &unknown; 1 < 2</div></body></html>"#;
+        assert_eq!(
+            html_to_text(html),
+            "Visible text © 2024 — ✓ &unknown; 1 < 2"
+        );
+    }
+
+    #[test]
+    fn html_to_text_should_handle_breaks_pre_and_attributes_with_brackets() {
+        let html = "<h1>Title</h1>Line one<br>Line two<br/><br />Line four<pre>  keep\n    this</pre><a title=\"a > b\" href=\"https://x.io\">x</a>";
+        assert_eq!(
+            html_to_text(html),
+            "Title\n\nLine one\nLine two\n\nLine four\n\n  keep\n    this\n\nx (https://x.io)"
+        );
+    }
+
+    #[test]
+    fn html_to_text_should_not_split_characters_when_a_link_starts_in_trimmed_spaces() {
+        let html = "<pre>a  <a href=\"https://x\"><br>é</a></pre>";
+        assert_eq!(html_to_text(html), "a\né (https://x)");
+    }
+
+    #[tokio::test]
+    async fn text_alternative_should_fill_missing_text() {
+        let fake = FakeResend::new();
+        let mut client = fake.client();
+        client.email_service.add_interceptor(TextAlternative);
+
+        let email = SendEmailRequest {
+            html: Some("<p>Hello <b>World</b></p>".to_string()),
+            text: None,
+            ..send_email_request()
+        };
+        client.email_service.send(&email).await.unwrap();
+        let with_text = SendEmailRequest {
+            text: Some("Handwritten".to_string()),
+            ..email
+        };
+        client.email_service.send(&with_text).await.unwrap();
+
+        let sent = fake.sent_emails();
+        assert_eq!(sent[0].text.as_deref(), Some("Hello World"));
+        assert_eq!(sent[1].text.as_deref(), Some("Handwritten"));
+    }
+}
@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod redirect_tests {
+    use resend_client_rs::{
+        emails::SendEmailRequest,
+        redirect::{RecipientRedirect, ORIGINAL_BCC_HEADER, ORIGINAL_TO_HEADER},
+        testing::FakeResend,
+    };
+
+    use crate::fixtures::send_email_request;
+
+    #[tokio::test]
+    async fn recipients_should_be_redirected() {
+        let fake = FakeResend::new();
+        let mut client = fake.client();
+        client.email_service.add_interceptor(
+            RecipientRedirect::new("inbox@qa.example.com").subject_prefix("[STAGING]"),
+        );
+
+        let email = SendEmailRequest {
+            to: vec!["customer@gmail.com".to_string()],
+            cc: Some(vec!["Accounting <billing@customer.com>".to_string()]),
+            bcc: Some(vec!["archive@qa.example.com".to_string()]),
+            ..send_email_request()
+        };
+        client.email_service.send(&email).await.unwrap();
+
+        let sent = &fake.sent_emails()[0];
+        assert_eq!(sent.to, vec!["inbox@qa.example.com"]);
+        assert_eq!(sent.cc, None);
+        assert_eq!(sent.bcc, None);
+        assert_eq!(sent.subject, "[STAGING] My subject");
+        let headers = sent.headers.as_ref().unwrap();
+        assert_eq!(
+            headers[ORIGINAL_TO_HEADER],
+            "customer@gmail.com, Accounting <billing@customer.com>"
+        );
+        // the redirect inbox is the only recipient left, so nobody else sees the bcc
+        assert_eq!(headers[ORIGINAL_BCC_HEADER], "archive@qa.example.com");
+    }
+
+    #[tokio::test]
+    async fn allowed_domains_should_keep_their_recipients() {
+        let fake = FakeResend::new();
+        let mut client = fake.client();
+        client.email_service.add_interceptor(
+            RecipientRedirect::new("inbox@qa.example.com")
+                .subject_prefix("[STAGING]")
+                .allow_domain("qa.example.com"),
+        );
+
+        let email = SendEmailRequest {
+            to: vec!["customer@gmail.com".to_string()],
+            cc: Some(vec!["Accounting <billing@customer.com>".to_string()]),
+            bcc: Some(vec!["archive@qa.example.com".to_string()]),
+            ..send_email_request()
+        };
+        client.email_service.send(&email).await.unwrap();
+        let allowed = SendEmailRequest {
+            to: vec!["tester@QA.example.com".to_string()],
+            cc: None,
+            ..email
+        };
+        client.email_service.send(&allowed).await.unwrap();
+
+        let sent = fake.sent_emails();
+        assert_eq!(sent[0].to, vec!["inbox@qa.example.com"]);
+        assert_eq!(
+            sent[0].bcc,
+            Some(vec!["archive@qa.example.com".to_string()])
+        );
+        // the surviving bcc recipient must not learn about itself or other bcc recipients
+        let headers = sent[0].headers.as_ref().unwrap();
+        assert!(!headers[ORIGINAL_TO_HEADER].contains("archive@qa.example.com"));
+        assert!(!headers.contains_key(ORIGINAL_BCC_HEADER));
+        assert_eq!(sent[1].to, vec!["tester@QA.example.com"]);
+        assert_eq!(sent[1].subject, "[STAGING] My subject");
+        assert!(sent[1].headers.is_none());
+    }
+
+    #[tokio::test]
+    async fn batch_sends_should_be_redirected() {
+        let fake = FakeResend::new();
+        let mut client = fake.client();
+        client
+            .email_service
+            .add_interceptor(RecipientRedirect::new("inbox@qa.example.com"));
+
+        let email = SendEmailRequest {
+            to: vec!["customer@gmail.com".to_string()],
+            ..send_email_request()
+        };
+        let batch = vec![email.clone(), email];
+        client.email_service.send_batch(&batch).await.unwrap();
+
+        let sent = fake.sent_emails();
+        assert_eq!(sent.len(), 2);
+        assert!(sent
+            .iter()
+            .all(|email| email.to == vec!["inbox@qa.example.com"]));
+        assert_eq!(sent[0].subject, "My subject");
+    }
+}
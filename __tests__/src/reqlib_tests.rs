@@ -0,0 +1,167 @@
+#[cfg(test)]
+mod reqlib_tests {
+    use std::time::Duration;
+
+    use http::header::HeaderName;
+    use http::{HeaderMap, HeaderValue, Method, StatusCode};
+    use resend_client_rs::{
+        domains::{CreateDomainRequest, ListDomainsResponse},
+        dry_run::DryRun,
+        emails::{SendEmailRequest, SendEmailResponse},
+        middleware::DefaultHeaders,
+        reqlib::{APIResponse, Error, RateLimitInfo},
+        testing::{FakeResend, TEST_API_KEY},
+    };
+
+    use crate::fixtures::send_email_request;
+
+    #[test]
+    fn rate_limit_info_should_parse_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("ratelimit-limit", HeaderValue::from_static("10"));
+        headers.insert("ratelimit-remaining", HeaderValue::from_static("7"));
+        headers.insert("ratelimit-reset", HeaderValue::from_static("2"));
+        assert_eq!(
+            RateLimitInfo::from_headers(&headers),
+            Some(RateLimitInfo {
+                limit: 10,
+                remaining: 7,
+                reset: Duration::from_secs(2),
+            })
+        );
+
+        headers.insert("ratelimit-remaining", HeaderValue::from_static("many"));
+        assert_eq!(RateLimitInfo::from_headers(&headers), None);
+        assert_eq!(RateLimitInfo::from_headers(&HeaderMap::new()), None);
+    }
+
+    #[tokio::test]
+    async fn send_with_meta_should_expose_response_metadata() {
+        let fake = FakeResend::new();
+        fake.set_rate_limit(5);
+        let client = fake.client();
+        let response = client
+            .email_service
+            .send_with_meta(&send_email_request())
+            .await;
+
+        assert!(matches!(response.result, Ok(APIResponse::Success(_))));
+        let meta = response.meta.unwrap();
+        assert_eq!(meta.status, StatusCode::OK);
+        assert_eq!(meta.request_id.as_deref(), Some("req_000000000001"));
+        assert_eq!(
+            meta.rate_limit,
+            Some(RateLimitInfo {
+                limit: 5,
+                remaining: 4,
+                reset: Duration::from_secs(1),
+            })
+        );
+        assert!(meta.headers.contains_key("content-type"));
+    }
+
+    #[tokio::test]
+    async fn error_responses_should_keep_metadata() {
+        let fake = FakeResend::new();
+        fake.fail_next(Method::POST, "domains", StatusCode::FORBIDDEN, "forbidden");
+        let client = fake.client();
+        let response = client
+            .domain_service
+            .create_with_meta(&CreateDomainRequest::builder("example.com").build())
+            .await;
+
+        assert!(matches!(response.result, Err(APIResponse::Error(_))));
+        let meta = response.meta.unwrap();
+        assert_eq!(meta.status, StatusCode::FORBIDDEN);
+        assert!(meta.request_id.is_some());
+        assert_eq!(meta.rate_limit, None);
+    }
+
+    #[tokio::test]
+    async fn local_results_should_have_no_metadata() {
+        let fake = FakeResend::new();
+        let mut client = fake.client();
+        let invalid = SendEmailRequest {
+            to: vec![],
+            ..send_email_request()
+        };
+        let response = client.email_service.send_with_meta(&invalid).await;
+        assert!(matches!(
+            response.result,
+            Err(APIResponse::Failure(Error::Validation(_)))
+        ));
+        assert!(response.meta.is_none());
+
+        client.enable_dry_run(DryRun::new());
+        let response = client
+            .email_service
+            .send_with_meta(&send_email_request())
+            .await;
+        assert!(matches!(response.result, Ok(APIResponse::Success(_))));
+        assert!(response.meta.is_none());
+        assert!(fake.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn request_should_send_query_and_auth_through_middleware() {
+        let fake = FakeResend::new();
+        let mut client = fake.client();
+        client.add_middleware(DefaultHeaders::new().header(
+            HeaderName::from_static("x-team"),
+            HeaderValue::from_static("growth"),
+        ));
+        let response = client
+            .request::<(), ListDomainsResponse>(
+                Method::GET,
+                "domains",
+                &[("limit", "10"), ("after", "a b")],
+                None,
+            )
+            .await;
+        assert!(matches!(response, Ok(APIResponse::Success(ref list)) if list.data.is_empty()));
+
+        let request = &fake.requests()[0];
+        assert_eq!(request.url.query(), Some("limit=10&after=a+b"));
+        assert_eq!(
+            request.headers.get("authorization").unwrap(),
+            &format!("Bearer {}", TEST_API_KEY)
+        );
+        assert_eq!(request.headers.get("x-team").unwrap(), "growth");
+        assert!(request.body.is_none());
+    }
+
+    #[tokio::test]
+    async fn request_should_serialize_body_and_parse_response() {
+        let fake = FakeResend::new();
+        let client = fake.client();
+        let body = serde_json::json!({
+            "from": "from@domain.com",
+            "to": ["to@domain.com"],
+            "subject": "Raw",
+            "text": "Hello",
+        });
+        let response = client
+            .request_with_meta::<_, SendEmailResponse>(Method::POST, "emails", &[], Some(&body))
+            .await;
+        assert!(matches!(response.result, Ok(APIResponse::Success(_))));
+        assert_eq!(response.meta.unwrap().status, StatusCode::OK);
+        assert_eq!(fake.sent_emails()[0].subject, "Raw");
+        assert_eq!(
+            fake.requests()[0].headers.get("content-type").unwrap(),
+            "application/json"
+        );
+    }
+
+    #[tokio::test]
+    async fn request_should_map_api_errors() {
+        let fake = FakeResend::new();
+        let client = fake.client();
+        let response = client
+            .request::<(), serde_json::Value>(Method::DELETE, "emails", &[], None)
+            .await;
+        match response {
+            Err(APIResponse::Error(err)) => assert_eq!(err.status_code, 405),
+            other => panic!("unexpected response {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod secret_tests {
+    use std::fs;
+
+    use resend_client_rs::{
+        secret::{redact_addresses, ApiKey},
+        Client,
+    };
+
+    const KEY: &str = "re_super_secret";
+
+    #[test]
+    fn api_key_should_be_redacted_in_debug_and_display() {
+        let key = ApiKey::new(KEY);
+        assert_eq!(format!("{:?}", key), r#"ApiKey("[REDACTED]")"#);
+        assert_eq!(key.to_string(), "[REDACTED]");
+    }
+
+    #[test]
+    fn client_debug_should_not_contain_api_key() {
+        let client = Client::new(KEY);
+        let debug = format!("{:?}", client);
+        assert!(debug.contains("[REDACTED]"));
+        assert!(!debug.contains(KEY));
+
+        let client = resend_client_rs::blocking::Client::new(KEY);
+        assert!(!format!("{:?}", client).contains(KEY));
+    }
+
+    #[test]
+    fn request_headers_debug_should_not_contain_api_key() {
+        let client = Client::new(KEY);
+        let req = client.raw_client.new_request(http::Method::GET, "emails");
+        assert!(!format!("{:?}", req).contains(KEY));
+        assert_eq!(
+            req.headers.get("authorization").unwrap().to_str().unwrap(),
+            format!("Bearer {}", KEY)
+        );
+    }
+
+    #[test]
+    fn api_key_should_be_read_from_file() {
+        let path = std::env::temp_dir().join("resend_client_rs_api_key");
+        fs::write(&path, format!("  {}\n", KEY)).unwrap();
+        let key = ApiKey::from_file(&path).unwrap();
+        assert_eq!(key, ApiKey::new(KEY));
+
+        fs::write(&path, "\n").unwrap();
+        assert!(ApiKey::from_file(&path).is_err());
+        fs::remove_file(&path).unwrap();
+        assert!(ApiKey::from_file(&path).is_err());
+    }
+
+    #[test]
+    fn client_from_key_file_should_authenticate_with_file_key() {
+        let path = std::env::temp_dir().join("resend_client_rs_client_key");
+        fs::write(&path, KEY).unwrap();
+        let client = Client::from_key_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let req = client
+            .email_service
+            .req_client
+            .new_request(http::Method::GET, "emails");
+        assert_eq!(
+            req.headers.get("authorization").unwrap().to_str().unwrap(),
+            format!("Bearer {}", KEY)
+        );
+    }
+
+    #[test]
+    fn redact_addresses_should_work() {
+        assert_eq!(
+            redact_addresses("Jane <jane.doe+news@mail.example.co.uk>, bob@x.io."),
+            "Jane <redacted@example.com>, redacted@example.com."
+        );
+        assert_eq!(redact_addresses("@handle and a@b"), "@handle and a@b");
+    }
+}
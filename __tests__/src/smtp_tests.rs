@@ -0,0 +1,319 @@
+#[cfg(test)]
+mod smtp_tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::{TcpListener, TcpStream};
+
+    use resend_client_rs::{
+        emails::{Attachment, SendEmailRequest},
+        merge::{MailMerge, Recipient},
+        reqlib::{APIResponse, Error},
+        smtp::{SmtpSecurity, SmtpTransport},
+        Client,
+    };
+
+    use crate::fixtures::send_email_request;
+
+    const API_KEY: &str = "re_test";
+    // base64 of "\0resend\0re_test"
+    const AUTH_PLAIN: &str = "AHJlc2VuZAByZV90ZXN0";
+
+    #[derive(Debug, Clone, Default)]
+    struct Delivery {
+        from: String,
+        to: Vec<String>,
+        data: String,
+    }
+
+    // a local SMTP server that accepts AUTH PLAIN for `API_KEY` and keeps what it receives.
+    // recipients starting with `reject` are refused, and mail to recipients starting with
+    // `noid` is accepted without a queue id
+    struct SmtpSink {
+        port: u16,
+        deliveries: Arc<Mutex<Vec<Delivery>>>,
+    }
+
+    impl SmtpSink {
+        async fn start() -> SmtpSink {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let port = listener.local_addr().unwrap().port();
+            let deliveries = Arc::new(Mutex::new(vec![]));
+            let stored = deliveries.clone();
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(session(stream, stored.clone()));
+                }
+            });
+            SmtpSink { port, deliveries }
+        }
+
+        fn client(&self, api_key: &str) -> Client {
+            let transport =
+                SmtpTransport::relay("127.0.0.1", self.port).security(SmtpSecurity::None);
+            Client::with_transport(api_key, Arc::new(transport))
+        }
+
+        fn deliveries(&self) -> Vec<Delivery> {
+            self.deliveries.lock().unwrap().clone()
+        }
+    }
+
+    async fn session(stream: TcpStream, deliveries: Arc<Mutex<Vec<Delivery>>>) {
+        let (read, mut write) = stream.into_split();
+        let mut lines = BufReader::new(read).lines();
+        let mut delivery = Delivery::default();
+        write.write_all(b"220 sink ESMTP\r\n").await.unwrap();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let upper = line.to_ascii_uppercase();
+            let reply = if upper.starts_with("EHLO") {
+                "250-sink\r\n250-AUTH PLAIN\r\n250 8BITMIME".to_string()
+            } else if upper.starts_with("AUTH PLAIN") {
+                match line.split_whitespace().nth(2) == Some(AUTH_PLAIN) {
+                    true => "235 2.7.0 Authentication successful".to_string(),
+                    false => "535 5.7.8 Authentication credentials invalid".to_string(),
+                }
+            } else if upper.starts_with("MAIL FROM:") {
+                delivery = Delivery::default();
+                delivery.from = address(&line);
+                "250 2.1.0 OK".to_string()
+            } else if upper.starts_with("RCPT TO:") {
+                let to = address(&line);
+                match to.starts_with("reject") {
+                    true => "550 5.1.1 No such user".to_string(),
+                    false => {
+                        delivery.to.push(to);
+                        "250 2.1.5 OK".to_string()
+                    }
+                }
+            } else if upper == "DATA" {
+                write.write_all(b"354 Go ahead\r\n").await.unwrap();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if line == "." {
+                        break;
+                    }
+                    let line = line.strip_prefix('.').unwrap_or(&line);
+                    delivery.data.push_str(line);
+                    delivery.data.push('\n');
+                }
+                let mut deliveries = deliveries.lock().unwrap();
+                let without_id = delivery.to.iter().any(|to| to.starts_with("noid"));
+                deliveries.push(std::mem::take(&mut delivery));
+                match without_id {
+                    true => "250 2.0.0 OK".to_string(),
+                    false => format!("250 2.0.0 OK queued as <msg-{}>", deliveries.len()),
+                }
+            } else if upper == "QUIT" {
+                write.write_all(b"221 2.0.0 Bye\r\n").await.unwrap();
+                break;
+            } else if upper == "RSET" || upper == "NOOP" {
+                "250 2.0.0 OK".to_string()
+            } else {
+                "502 5.5.1 Not implemented".to_string()
+            };
+            write
+                .write_all(format!("{}\r\n", reply).as_bytes())
+                .await
+                .unwrap();
+        }
+    }
+
+    fn address(line: &str) -> String {
+        let start = line.find('<').map_or(0, |start| start + 1);
+        let end = line.rfind('>').unwrap_or(line.len());
+        line[start..end].to_string()
+    }
+
+    #[tokio::test]
+    async fn send_should_deliver_over_smtp() {
+        let sink = SmtpSink::start().await;
+        let client = sink.client(API_KEY);
+        let invoice = Attachment::new("invoice.pdf", b"%PDF-1.4".to_vec());
+
+        let email = SendEmailRequest {
+            from: "Acme <from@domain.com>".to_string(),
+            cc: Some(vec!["Ann <cc@domain.com>".to_string()]),
+            bcc: Some(vec!["bcc@domain.com".to_string()]),
+            html: Some("<p>Hello</p>".to_string()),
+            attachments: Some(vec![&invoice]),
+            ..send_email_request()
+        };
+
+        let response = client.email_service.send(&email).await;
+
+        match response {
+            Ok(APIResponse::Success(sent)) => assert_eq!(sent.id, "msg-1"),
+            other => panic!("unexpected response {:?}", other),
+        }
+        let deliveries = sink.deliveries();
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].from, "from@domain.com");
+        assert_eq!(
+            deliveries[0].to,
+            vec!["to@domain.com", "cc@domain.com", "bcc@domain.com"]
+        );
+        let data = &deliveries[0].data;
+        assert!(data.contains("From: \"Acme\" <from@domain.com>"));
+        assert!(data.contains("Subject: My subject"));
+        assert!(data.contains("multipart/alternative"));
+        assert!(data.contains("filename=\"invoice.pdf\""));
+        assert!(!data.contains("bcc@domain.com"));
+    }
+
+    #[tokio::test]
+    async fn send_batch_should_deliver_each_email() {
+        let sink = SmtpSink::start().await;
+        let client = sink.client(API_KEY);
+        let second = SendEmailRequest {
+            to: vec!["other@domain.com".to_string()],
+            ..send_email_request()
+        };
+
+        let response = client
+            .email_service
+            .send_batch(&[send_email_request(), second])
+            .await;
+
+        match response {
+            Ok(APIResponse::Success(batch)) => {
+                let ids: Vec<_> = batch.data.iter().map(|sent| sent.id.as_str()).collect();
+                assert_eq!(ids, vec!["msg-1", "msg-2"]);
+            }
+            other => panic!("unexpected response {:?}", other),
+        }
+        assert_eq!(sink.deliveries()[1].to[0], "other@domain.com");
+    }
+
+    #[tokio::test]
+    async fn send_batch_should_report_delivered_ids_when_a_later_email_is_refused() {
+        let sink = SmtpSink::start().await;
+        let client = sink.client(API_KEY);
+        let rejected = SendEmailRequest {
+            to: vec!["reject@domain.com".to_string()],
+            ..send_email_request()
+        };
+
+        let response = client
+            .email_service
+            .send_batch(&[send_email_request(), rejected, send_email_request()])
+            .await;
+
+        match response {
+            Err(APIResponse::Failure(Error::PartialBatch {
+                delivered,
+                failed_at,
+                error,
+            })) => {
+                assert_eq!(delivered, vec!["msg-1"]);
+                assert_eq!(failed_at, 1);
+                assert_eq!(error.status_code, 422);
+                assert!(error.message.contains("No such user"), "{}", error.message);
+            }
+            other => panic!("unexpected response {:?}", other),
+        }
+        // the batch stops at the refused email
+        assert_eq!(sink.deliveries().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn send_should_fall_back_to_the_message_id_without_a_queue_id() {
+        let sink = SmtpSink::start().await;
+        let email = SendEmailRequest {
+            to: vec!["noid@domain.com".to_string()],
+            ..send_email_request()
+        };
+
+        let response = sink.client(API_KEY).email_service.send(&email).await;
+
+        let id = match response {
+            Ok(APIResponse::Success(sent)) => sent.id,
+            other => panic!("unexpected response {:?}", other),
+        };
+        assert!(!id.is_empty());
+        assert_ne!(id, "OK");
+        let data = &sink.deliveries()[0].data;
+        assert!(data.contains(&format!("Message-ID: <{}>", id)), "{}", data);
+    }
+
+    #[tokio::test]
+    async fn send_should_map_smtp_errors_to_api_errors() {
+        let sink = SmtpSink::start().await;
+
+        let response = sink
+            .client("re_wrong")
+            .email_service
+            .send(&send_email_request())
+            .await;
+        match response {
+            Err(APIResponse::Error(err)) => {
+                assert_eq!(err.status_code, 401);
+                assert_eq!(err.name, "invalid_api_key");
+            }
+            other => panic!("unexpected response {:?}", other),
+        }
+
+        let rejected = SendEmailRequest {
+            to: vec!["reject@domain.com".to_string()],
+            ..send_email_request()
+        };
+        let response = sink.client(API_KEY).email_service.send(&rejected).await;
+        match response {
+            Err(APIResponse::Error(err)) => {
+                assert_eq!(err.status_code, 422);
+                assert!(err.message.contains("No such user"), "{}", err.message);
+            }
+            other => panic!("unexpected response {:?}", other),
+        }
+        assert!(sink.deliveries().is_empty());
+    }
+
+    #[tokio::test]
+    async fn other_endpoints_should_not_be_available() {
+        let sink = SmtpSink::start().await;
+        match sink.client(API_KEY).domain_service.list().await {
+            Err(APIResponse::Error(err)) => assert_eq!(err.status_code, 501),
+            other => panic!("unexpected response {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn send_should_fail_when_the_server_is_unreachable() {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let transport = SmtpTransport::relay("127.0.0.1", port).security(SmtpSecurity::None);
+        let client = Client::with_transport(API_KEY, Arc::new(transport));
+        match client.email_service.send(&send_email_request()).await {
+            Err(APIResponse::Failure(Error::Transport(_))) => {}
+            other => panic!("unexpected response {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn send_merge_should_report_emails_delivered_before_a_refusal() {
+        let sink = SmtpSink::start().await;
+        let client = sink.client(API_KEY);
+        let merge = MailMerge::new(SendEmailRequest {
+            text: Some("Hi {{name}}".to_string()),
+            ..send_email_request()
+        });
+        let recipients = ["ann@domain.com", "reject@domain.com", "bob@domain.com"]
+            .map(|to| Recipient::new(to, HashMap::from([("name", "you")])));
+
+        let report = client
+            .email_service
+            .send_merge(&merge, recipients)
+            .await
+            .unwrap();
+
+        assert_eq!(report.results[0].to, "ann@domain.com");
+        assert_eq!(report.results[0].result.as_deref().unwrap(), "msg-1");
+        assert!(report.results[1].result.is_err());
+        assert!(report.results[2].result.is_err());
+        assert_eq!(sink.deliveries().len(), 1);
+    }
+}
@@ -0,0 +1,151 @@
+#[cfg(test)]
+mod templates_tests {
+    use askama::Template;
+    use resend_client_rs::templates::{
+        askama::{AskamaEmail, AskamaTemplate},
+        handlebars::HandlebarsTemplate,
+        minijinja::MiniJinjaTemplate,
+        EmailPart, EmailTemplate, TemplateErrorKind,
+    };
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Welcome {
+        name: String,
+        plan: String,
+        features: Vec<String>,
+    }
+
+    fn welcome() -> Welcome {
+        Welcome {
+            name: "Ada & Co".to_string(),
+            plan: "Pro".to_string(),
+            features: vec!["Domains".to_string(), "Webhooks".to_string()],
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Partial {
+        name: String,
+    }
+
+    #[derive(Template)]
+    #[template(
+        source = "<h1>Welcome {{ name }}</h1><ul>{% for feature in features %}<li>{{ feature }}</li>{% endfor %}</ul>",
+        ext = "html"
+    )]
+    struct WelcomeHtml<'a> {
+        name: &'a str,
+        plan: &'a str,
+        features: &'a [String],
+    }
+
+    #[derive(Template)]
+    #[template(source = "Welcome {{ name }}, you are on {{ plan }}.", ext = "txt")]
+    struct WelcomeText<'a> {
+        name: &'a str,
+        plan: &'a str,
+    }
+
+    impl AskamaEmail for WelcomeHtml<'_> {
+        fn subject(&self) -> String {
+            format!("Welcome to {}, {}", self.plan, self.name)
+        }
+
+        fn text(&self) -> askama::Result<Option<String>> {
+            WelcomeText {
+                name: self.name,
+                plan: self.plan,
+            }
+            .render()
+            .map(Some)
+        }
+    }
+
+    #[test]
+    fn minijinja_should_render_email() {
+        let template = MiniJinjaTemplate::new(
+            "Welcome to {{ plan }}, {{ name }}",
+            Some("<h1>Welcome {{ name }}</h1><ul>{% for feature in features %}<li>{{ feature }}</li>{% endfor %}</ul>"),
+            Some("Welcome {{ name }}, you are on {{ plan }}."),
+        )
+        .unwrap();
+        insta::assert_debug_snapshot!(template.render(&welcome()).unwrap());
+    }
+
+    #[test]
+    fn handlebars_should_render_email() {
+        let template = HandlebarsTemplate::new(
+            "Welcome to {{plan}}, {{name}}",
+            Some("<h1>Welcome {{name}}</h1><ul>{{#each features}}<li>{{this}}</li>{{/each}}</ul>"),
+            Some("Welcome {{name}}, you are on {{plan}}."),
+        )
+        .unwrap();
+        insta::assert_debug_snapshot!(template.render(&welcome()).unwrap());
+    }
+
+    #[test]
+    fn askama_should_render_email() {
+        let context = welcome();
+        let html = WelcomeHtml {
+            name: &context.name,
+            plan: &context.plan,
+            features: &context.features,
+        };
+        insta::assert_debug_snapshot!(AskamaTemplate.render(&html).unwrap());
+    }
+
+    #[test]
+    fn render_request_should_fill_send_email_request() {
+        let template =
+            MiniJinjaTemplate::new("Hi {{ name }}", None, Some("Hello {{ name }}")).unwrap();
+        let request = template
+            .render_request(
+                &Partial {
+                    name: "Ada".to_string(),
+                },
+                "from@domain.com",
+                vec!["to@domain.com".to_string()],
+            )
+            .unwrap();
+        assert_eq!(request.subject, "Hi Ada");
+        assert_eq!(request.text.as_deref(), Some("Hello Ada"));
+        assert_eq!(request.html, None);
+        assert_eq!(request.from, "from@domain.com");
+        assert!(request.validate().is_ok());
+    }
+
+    #[test]
+    fn missing_variables_should_fail() {
+        let partial = Partial {
+            name: "Ada".to_string(),
+        };
+
+        let template =
+            MiniJinjaTemplate::new("Hi {{ name }}", Some("<p>{{ plan }}</p>"), None).unwrap();
+        let err = template.render(&partial).unwrap_err();
+        assert_eq!(err.part, EmailPart::Html);
+        assert_eq!(err.kind, TemplateErrorKind::MissingVariable);
+
+        let template =
+            HandlebarsTemplate::new("Hi {{name}}", None, Some("You are on {{plan}}")).unwrap();
+        let err = template.render(&partial).unwrap_err();
+        assert_eq!(err.part, EmailPart::Text);
+        assert_eq!(err.kind, TemplateErrorKind::MissingVariable);
+    }
+
+    #[test]
+    fn syntax_errors_should_fail_on_creation() {
+        let err = MiniJinjaTemplate::new("Hi {{ name", None, None)
+            .err()
+            .unwrap();
+        assert_eq!(err.part, EmailPart::Subject);
+        assert_eq!(err.kind, TemplateErrorKind::Syntax);
+
+        let err = HandlebarsTemplate::new("Hi", Some("{{#each items}}"), None)
+            .err()
+            .unwrap();
+        assert_eq!(err.part, EmailPart::Html);
+        assert_eq!(err.kind, TemplateErrorKind::Syntax);
+    }
+}
@@ -0,0 +1,182 @@
+#[cfg(test)]
+mod testing_tests {
+    use std::time::Duration;
+
+    use http::{Method, StatusCode};
+    use resend_client_rs::{
+        domains::{CreateDomainRequest, DomainStatus, Region, VerificationOutcome, WaitOptions},
+        emails::SendEmailRequest,
+        reqlib::APIResponse,
+        testing::FakeResend,
+    };
+
+    use crate::fixtures::send_email_request;
+
+    #[tokio::test]
+    async fn sent_emails_should_be_stored() {
+        let fake = FakeResend::new();
+        let client = fake.client();
+        let email = SendEmailRequest {
+            to: vec!["first@domain.com".to_string()],
+            cc: Some(vec!["cc@domain.com".to_string()]),
+            html: Some("<p>Hello World</p>".to_string()),
+            text: None,
+            ..send_email_request()
+        };
+
+        let first = match client.email_service.send(&email).await.unwrap() {
+            APIResponse::Success(data) => data,
+            other => panic!("unexpected result {:?}", other),
+        };
+        client
+            .email_service
+            .send(&SendEmailRequest {
+                to: vec!["second@domain.com".to_string()],
+                ..email
+            })
+            .await
+            .unwrap();
+
+        let sent = fake.sent_emails();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[0].id, first.id);
+        assert_eq!(sent[0].to, vec!["first@domain.com"]);
+        assert_eq!(sent[0].raw["html"], "<p>Hello World</p>");
+
+        let email = match client.email_service.get(first.id.clone()).await.unwrap() {
+            APIResponse::Success(data) => data,
+            other => panic!("unexpected result {:?}", other),
+        };
+        assert_eq!(email.subject, "My subject");
+        assert_eq!(email.cc, Some(vec![Some("cc@domain.com".to_string())]));
+
+        let list = match client.email_service.list().await.unwrap() {
+            APIResponse::Success(data) => data,
+            other => panic!("unexpected result {:?}", other),
+        };
+        assert_eq!(list.data.len(), 2);
+        assert_eq!(list.data[0].to, vec!["second@domain.com"]);
+
+        match client.email_service.get("missing").await {
+            Err(APIResponse::Error(err)) => assert_eq!(err.status_code, 404),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn domains_should_move_through_statuses() {
+        let fake = FakeResend::new();
+        let client = fake.client();
+
+        let created = match client
+            .domain_service
+            .create(
+                &CreateDomainRequest::builder("example.com")
+                    .region(Region::EuWest1)
+                    .build(),
+            )
+            .await
+            .unwrap()
+        {
+            APIResponse::Success(data) => data,
+            other => panic!("unexpected result {:?}", other),
+        };
+        assert_eq!(created.status, DomainStatus::NotStarted);
+        assert_eq!(created.region, Region::EuWest1);
+
+        client
+            .domain_service
+            .verify(created.id.clone())
+            .await
+            .unwrap();
+        let domain = match client.domain_service.get(created.id.clone()).await.unwrap() {
+            APIResponse::Success(data) => data,
+            other => panic!("unexpected result {:?}", other),
+        };
+        assert_eq!(domain.status, DomainStatus::Pending);
+        assert_eq!(
+            domain.records[0].value,
+            "feedback-smtp.eu-west-1.amazonses.com"
+        );
+
+        fake.set_domain_status(&created.id, DomainStatus::Verified);
+        let outcome = client
+            .domain_service
+            .wait_until_verified(
+                created.id.clone(),
+                &WaitOptions {
+                    initial_interval: Duration::from_millis(1),
+                    max_interval: Duration::from_millis(1),
+                    timeout: Duration::from_secs(1),
+                },
+            )
+            .await
+            .unwrap();
+        assert!(matches!(outcome, VerificationOutcome::Verified(_)));
+
+        let deleted = match client
+            .domain_service
+            .delete(created.id.clone())
+            .await
+            .unwrap()
+        {
+            APIResponse::Success(data) => data,
+            other => panic!("unexpected result {:?}", other),
+        };
+        assert!(deleted.deleted);
+        assert!(fake.domains().is_empty());
+        match client.domain_service.list().await.unwrap() {
+            APIResponse::Success(data) => assert!(data.data.is_empty()),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn injected_errors_should_be_returned_once() {
+        let fake = FakeResend::new();
+        let client = fake.client();
+        fake.fail_next(
+            Method::POST,
+            "emails",
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Invalid `from` field.",
+        );
+
+        match client.email_service.send(&send_email_request()).await {
+            Err(APIResponse::Error(err)) => {
+                assert_eq!(err.status_code, 422);
+                assert_eq!(err.name, "unprocessable_entity");
+                assert_eq!(err.message, "Invalid `from` field.");
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+        assert!(fake.sent_emails().is_empty());
+
+        assert!(client
+            .email_service
+            .send(&send_email_request())
+            .await
+            .is_ok());
+        assert_eq!(fake.sent_emails().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rate_limit_should_reject_extra_requests() {
+        let fake = FakeResend::new();
+        let client = fake.client();
+        fake.set_rate_limit(1);
+
+        assert!(client.domain_service.list().await.is_ok());
+        match client.domain_service.list().await {
+            Err(APIResponse::Error(err)) => {
+                assert_eq!(err.status_code, 429);
+                assert_eq!(err.name, "rate_limit_exceeded");
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        fake.reset_rate_limit();
+        assert!(client.domain_service.list().await.is_ok());
+        assert_eq!(fake.requests().len(), 3);
+    }
+}
@@ -0,0 +1,270 @@
+#[cfg(test)]
+mod tracing_tests {
+    use std::collections::HashMap;
+    use std::fmt;
+    use std::sync::{Arc, Mutex};
+
+    use http::{HeaderMap, HeaderValue, StatusCode};
+    use resend_client_rs::{
+        emails::SendEmailRequest,
+        middleware::{Middleware, Next},
+        reqlib::{APIResponse, REQUEST_ID_HEADER},
+        trace::TraceOptions,
+        transport::{Transport, TransportFuture, TransportRequest, TransportResponse},
+        Client,
+    };
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::Subscriber;
+    use tracing_subscriber::layer::{Context, SubscriberExt};
+    use tracing_subscriber::registry::LookupSpan;
+
+    use crate::fixtures::send_email_request;
+    use tracing_subscriber::Layer;
+
+    type Spans = Arc<Mutex<Vec<(String, HashMap<String, String>)>>>;
+
+    // keeps the fields of every span, in the order the spans were created
+    #[derive(Default)]
+    struct Capture {
+        spans: Spans,
+    }
+
+    struct Fields(HashMap<String, String>);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            self.0
+                .insert(field.name().to_string(), format!("{:?}", value));
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Capture {
+        fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+            let mut fields = Fields(HashMap::new());
+            attrs.record(&mut fields);
+            let mut spans = self.spans.lock().unwrap();
+            spans.push((attrs.metadata().name().to_string(), fields.0));
+            ctx.span(id)
+                .unwrap()
+                .extensions_mut()
+                .insert(spans.len() - 1);
+        }
+
+        fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+            let index = *ctx.span(id).unwrap().extensions().get::<usize>().unwrap();
+            let mut fields = Fields(HashMap::new());
+            values.record(&mut fields);
+            self.spans.lock().unwrap()[index].1.extend(fields.0);
+        }
+    }
+
+    struct FakeTransport {
+        status: StatusCode,
+        body: &'static str,
+    }
+
+    impl Transport for FakeTransport {
+        fn send(&self, _request: TransportRequest) -> TransportFuture<'_> {
+            let mut headers = HeaderMap::new();
+            headers.insert(REQUEST_ID_HEADER, HeaderValue::from_static("req_123"));
+            let response = TransportResponse {
+                status: self.status,
+                headers,
+                body: self.body.as_bytes().to_vec(),
+            };
+            Box::pin(async move { Ok(response) })
+        }
+    }
+
+    // runs the rest of the chain again when the first attempt is rejected
+    struct RetryOnce;
+
+    impl Middleware for RetryOnce {
+        fn handle<'a>(&'a self, request: TransportRequest, next: Next<'a>) -> TransportFuture<'a> {
+            Box::pin(async move {
+                let response = next.run(request.clone()).await?;
+                if response.status.is_success() {
+                    return Ok(response);
+                }
+                next.run(request).await
+            })
+        }
+    }
+
+    fn client(status: StatusCode, body: &'static str) -> Client {
+        Client::with_transport("re_secret_key", Arc::new(FakeTransport { status, body }))
+    }
+
+    async fn capture<F: std::future::Future>(future: F) -> Vec<(String, HashMap<String, String>)> {
+        let spans = Spans::default();
+        let subscriber = tracing_subscriber::registry().with(Capture {
+            spans: spans.clone(),
+        });
+        let _guard = tracing::subscriber::set_default(subscriber);
+        future.await;
+        let spans = spans.lock().unwrap().clone();
+        spans
+    }
+
+    fn request_span(spans: &[(String, HashMap<String, String>)]) -> &HashMap<String, String> {
+        &spans
+            .iter()
+            .find(|(name, _)| name == "resend.request")
+            .unwrap()
+            .1
+    }
+
+    #[tokio::test]
+    async fn exec_should_record_request_span() {
+        let client = client(StatusCode::OK, r#"{"id":"mock-id"}"#);
+        let spans = capture(async {
+            let response = client.email_service.send(&send_email_request()).await;
+            assert!(matches!(response, Ok(APIResponse::Success(_))));
+        })
+        .await;
+
+        let fields = request_span(&spans);
+        assert_eq!(fields["http.method"], "POST");
+        assert_eq!(fields["url.path"], "/emails");
+        assert_eq!(fields["http.status_code"], "200");
+        assert_eq!(fields["transport_calls"], "1");
+        assert_eq!(fields["resend.request_id"], "req_123");
+        assert!(fields.contains_key("latency_ms"));
+        assert!(spans
+            .iter()
+            .any(|(name, fields)| name == "resend.transport" && fields["call"] == "1"));
+    }
+
+    #[tokio::test]
+    async fn exec_should_redact_by_default() {
+        let client = client(StatusCode::OK, r#"{"id":"mock-id"}"#);
+        let email = SendEmailRequest {
+            cc: Some(vec!["cc@domain.com".to_string()]),
+            ..send_email_request()
+        };
+        let spans = capture(async {
+            let _ = client.email_service.send(&email).await;
+        })
+        .await;
+
+        let fields = request_span(&spans);
+        assert_eq!(
+            fields["recipients"],
+            "redacted@example.com, redacted@example.com"
+        );
+        assert!(!fields.contains_key("request.body"));
+        assert!(!fields.contains_key("response.body"));
+        let everything = format!("{:?}", spans);
+        assert!(!everything.contains("to@domain.com"));
+        assert!(!everything.contains("re_secret_key"));
+    }
+
+    #[tokio::test]
+    async fn exec_should_record_recipients_and_bodies_when_enabled() {
+        let mut client = client(StatusCode::OK, r#"{"id":"mock-id"}"#);
+        client.email_service.req_client.trace = TraceOptions::new().with_recipients().with_bodies();
+        let email = SendEmailRequest {
+            cc: Some(vec!["cc@domain.com".to_string()]),
+            ..send_email_request()
+        };
+        let spans = capture(async {
+            let _ = client.email_service.send(&email).await;
+        })
+        .await;
+
+        let fields = request_span(&spans);
+        assert_eq!(fields["recipients"], "to@domain.com, cc@domain.com");
+        assert!(fields["request.body"].contains("Hello World"));
+        assert_eq!(fields["response.body"], r#"{"id":"mock-id"}"#);
+        assert!(!format!("{:?}", spans).contains("re_secret_key"));
+    }
+
+    #[tokio::test]
+    async fn recorded_bodies_should_keep_addresses_redacted() {
+        let mut client = client(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            r#"{"name":"validation_error","status_code":422,"message":"to@domain.com is invalid"}"#,
+        );
+        client.email_service.req_client.trace = TraceOptions::new().with_bodies();
+        let email = SendEmailRequest {
+            cc: Some(vec!["cc@domain.com".to_string()]),
+            ..send_email_request()
+        };
+        let spans = capture(async {
+            let _ = client.email_service.send(&email).await;
+        })
+        .await;
+
+        let fields = request_span(&spans);
+        assert!(fields["request.body"].contains("Hello World"));
+        assert!(fields["request.body"].contains("redacted@example.com"));
+        assert!(fields["response.body"].contains("redacted@example.com is invalid"));
+        let everything = format!("{:?}", spans);
+        assert!(!everything.contains("to@domain.com"));
+        assert!(!everything.contains("cc@domain.com"));
+        assert!(!everything.contains("from@domain.com"));
+    }
+
+    #[tokio::test]
+    async fn exec_should_record_redacted_errors_and_transport_calls() {
+        let mut client = client(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            r#"{"name":"validation_error","status_code":422,"message":"to@domain.com is invalid"}"#,
+        );
+        client.email_service.req_client.add_middleware(RetryOnce);
+        let spans = capture(async {
+            let response = client.email_service.send(&send_email_request()).await;
+            assert!(matches!(response, Err(APIResponse::Error(_))));
+        })
+        .await;
+
+        let fields = request_span(&spans);
+        assert_eq!(fields["http.status_code"], "422");
+        assert_eq!(fields["transport_calls"], "2");
+        assert_eq!(
+            fields["error"],
+            "validation_error: redacted@example.com is invalid"
+        );
+    }
+
+    #[tokio::test]
+    async fn client_side_failures_should_record_request_span() {
+        let mut client = client(StatusCode::OK, r#"{"id":"mock-id"}"#);
+        let invalid = SendEmailRequest {
+            to: vec![],
+            ..send_email_request()
+        };
+        let spans = capture(async {
+            let response = client.email_service.send(&invalid).await;
+            assert!(matches!(response, Err(APIResponse::Failure(_))));
+        })
+        .await;
+        let fields = request_span(&spans);
+        assert_eq!(fields["http.method"], "POST");
+        assert_eq!(fields["url.path"], "/emails");
+        assert_eq!(
+            fields["error"],
+            "invalid `to`: at least one recipient is required"
+        );
+
+        client
+            .email_service
+            .req_client
+            .headers
+            .insert("bad header".to_string(), "value".to_string());
+        let spans = capture(async {
+            let response = client.email_service.send(&send_email_request()).await;
+            assert!(matches!(response, Err(APIResponse::Failure(_))));
+        })
+        .await;
+        let fields = request_span(&spans);
+        assert_eq!(fields["url.path"], "/emails");
+        assert!(fields["error"].contains("invalid header `bad header`"));
+        assert!(!fields.contains_key("transport_calls"));
+    }
+}
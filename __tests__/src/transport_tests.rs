@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod transport_tests {
+    use std::sync::{Arc, Mutex};
+
+    use http::{HeaderMap, StatusCode};
+    use resend_client_rs::{
+        reqlib::{APIResponse, Error},
+        transport::{Transport, TransportFuture, TransportRequest, TransportResponse},
+        Client,
+    };
+
+    use crate::fixtures::send_email_request;
+
+    struct FakeTransport {
+        status: StatusCode,
+        body: &'static str,
+        requests: Mutex<Vec<TransportRequest>>,
+    }
+
+    impl FakeTransport {
+        fn new(status: StatusCode, body: &'static str) -> Arc<Self> {
+            Arc::new(FakeTransport {
+                status,
+                body,
+                requests: Mutex::new(vec![]),
+            })
+        }
+    }
+
+    impl Transport for FakeTransport {
+        fn send(&self, request: TransportRequest) -> TransportFuture<'_> {
+            self.requests.lock().unwrap().push(request);
+            let response = TransportResponse {
+                status: self.status,
+                headers: HeaderMap::new(),
+                body: self.body.as_bytes().to_vec(),
+            };
+            Box::pin(async move { Ok(response) })
+        }
+    }
+
+    #[tokio::test]
+    async fn send_should_use_custom_transport() {
+        let transport = FakeTransport::new(StatusCode::OK, r#"{"id":"mock-id"}"#);
+        let client = Client::with_transport("api-key", transport.clone());
+
+        let result = client.email_service.send(&send_email_request()).await;
+        let data = match result.unwrap() {
+            APIResponse::Success(data) => Some(data),
+            _ => None,
+        };
+        assert_eq!(data.unwrap().id, "mock-id");
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, http::Method::POST);
+        assert_eq!(requests[0].url.as_str(), "https://api.resend.com/emails");
+        assert_eq!(requests[0].headers["authorization"], "Bearer api-key");
+        assert_eq!(requests[0].headers["content-type"], "application/json");
+        let body: serde_json::Value =
+            serde_json::from_slice(requests[0].body.as_ref().unwrap()).unwrap();
+        assert_eq!(body["subject"], "My subject");
+    }
+
+    #[tokio::test]
+    async fn error_body_should_map_to_error_response() {
+        let transport = FakeTransport::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            r#"{"name":"validation_error","status_code":422,"message":"Invalid `to` field"}"#,
+        );
+        let client = Client::with_transport("api-key", transport);
+
+        match client.email_service.send(&send_email_request()).await {
+            Err(APIResponse::Error(err)) => {
+                assert_eq!(err.name, "validation_error");
+                assert_eq!(err.status_code, 422);
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_error_body_should_map_to_status_failure() {
+        let transport = FakeTransport::new(StatusCode::BAD_GATEWAY, "<html>Bad Gateway</html>");
+        let client = Client::with_transport("api-key", transport);
+
+        match client.domain_service.list().await {
+            Err(APIResponse::Failure(Error::Status { status, body })) => {
+                assert_eq!(status, StatusCode::BAD_GATEWAY);
+                assert_eq!(body, "<html>Bad Gateway</html>");
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn invalid_custom_header_should_be_rejected_before_sending() {
+        let transport = FakeTransport::new(StatusCode::OK, r#"{"id":"mock-id"}"#);
+        let mut client = Client::with_transport("api-key", transport.clone());
+        client
+            .email_service
+            .req_client
+            .headers
+            .insert("X-Entity-Ref".into(), "line\nbreak".into());
+
+        match client.email_service.send(&send_email_request()).await {
+            Err(APIResponse::Failure(Error::Validation(err))) => {
+                assert_eq!(err.field, "headers");
+                assert!(err.message.contains("X-Entity-Ref"));
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+        assert!(transport.requests.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn custom_headers_should_keep_every_value() {
+        let transport = FakeTransport::new(StatusCode::OK, r#"{"id":"mock-id"}"#);
+        let mut client = Client::with_transport("api-key", transport.clone());
+        let headers = &mut client.email_service.req_client.headers;
+        headers.insert("X-Tag".into(), "first".into());
+        headers.insert("x-tag".into(), "second".into());
+
+        client
+            .email_service
+            .send(&send_email_request())
+            .await
+            .unwrap();
+        let requests = transport.requests.lock().unwrap();
+        let mut values: Vec<_> = requests[0].headers.get_all("x-tag").iter().collect();
+        values.sort();
+        assert_eq!(values, ["first", "second"]);
+    }
+}
@@ -0,0 +1,121 @@
+use std::time::Instant;
+
+use http::Method;
+
+use super::reqlib::ReqClient;
+use crate::domains::{
+    CreateDomainRequest, CreateDomainResponse, DeleteDomainResponse, DnsRecord, DomainDetails,
+    ListDomainsResponse, PollStep, Poller, VerificationOutcome, VerifyDomainResponse, WaitOptions,
+};
+use crate::reqlib::{APIResponse, Response};
+
+#[derive(Debug)]
+pub struct DomainService {
+    pub req_client: ReqClient,
+}
+
+impl DomainService {
+    pub fn new(req_client: ReqClient) -> DomainService {
+        DomainService { req_client }
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn create(
+        &self,
+        params: &CreateDomainRequest,
+    ) -> Result<APIResponse<CreateDomainResponse>, APIResponse<CreateDomainResponse>> {
+        self.create_with_meta(params).result
+    }
+
+    pub fn create_with_meta(&self, params: &CreateDomainRequest) -> Response<CreateDomainResponse> {
+        let req = self
+            .req_client
+            .new_body_request(Method::POST, "domains", Some(params));
+        self.req_client.exec_with_meta(req)
+    }
+
+    pub fn verify<T: Into<String>>(
+        &self,
+        domain_id: T,
+    ) -> Result<APIResponse<VerifyDomainResponse>, APIResponse<VerifyDomainResponse>> {
+        self.verify_with_meta(domain_id).result
+    }
+
+    pub fn verify_with_meta<T: Into<String>>(
+        &self,
+        domain_id: T,
+    ) -> Response<VerifyDomainResponse> {
+        let path = format!("domains/{}", domain_id.into());
+        let req = self.req_client.new_request(Method::POST, &path);
+        self.req_client.exec_with_meta(req)
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn get<T: Into<String>>(
+        &self,
+        domain_id: T,
+    ) -> Result<APIResponse<DomainDetails>, APIResponse<DomainDetails>> {
+        self.get_with_meta(domain_id).result
+    }
+
+    pub fn get_with_meta<T: Into<String>>(&self, domain_id: T) -> Response<DomainDetails> {
+        let path = format!("domains/{}", domain_id.into());
+        let req = self.req_client.new_request(Method::GET, &path);
+        self.req_client.exec_with_meta(req)
+    }
+
+    pub fn list(
+        &self,
+    ) -> Result<APIResponse<ListDomainsResponse>, APIResponse<ListDomainsResponse>> {
+        self.list_with_meta().result
+    }
+
+    pub fn list_with_meta(&self) -> Response<ListDomainsResponse> {
+        let req = self.req_client.new_request(Method::GET, "domains");
+        self.req_client.exec_with_meta(req)
+    }
+
+    pub fn delete<T: Into<String>>(
+        &self,
+        domain_id: T,
+    ) -> Result<APIResponse<DeleteDomainResponse>, APIResponse<DeleteDomainResponse>> {
+        self.delete_with_meta(domain_id).result
+    }
+
+    pub fn delete_with_meta<T: Into<String>>(
+        &self,
+        domain_id: T,
+    ) -> Response<DeleteDomainResponse> {
+        let path = format!("domains/{}", domain_id.into());
+        let req = self.req_client.new_request(Method::DELETE, &path);
+        self.req_client.exec_with_meta(req)
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn wait_until_verified<T: Into<String>>(
+        &self,
+        domain_id: T,
+        options: &WaitOptions,
+    ) -> Result<VerificationOutcome, APIResponse<DomainDetails>> {
+        self.wait_until_verified_with_progress(domain_id, options, |_| {})
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn wait_until_verified_with_progress<T: Into<String>, F: FnMut(&[DnsRecord])>(
+        &self,
+        domain_id: T,
+        options: &WaitOptions,
+        mut on_progress: F,
+    ) -> Result<VerificationOutcome, APIResponse<DomainDetails>> {
+        let domain_id = domain_id.into();
+        let started = Instant::now();
+        let mut poller = Poller::new(options);
+        loop {
+            let response = self.get(domain_id.clone());
+            match poller.step(response, started.elapsed(), &mut on_progress) {
+                PollStep::Finish(result) => return result,
+                PollStep::Sleep(delay) => std::thread::sleep(delay),
+            }
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use http::Method;
+use serde::Serialize;
+
+use super::reqlib::ReqClient;
+use crate::emails::{
+    check_batch_ids, validate_batch, Email, ListEmailsResponse, SendBatchResponse,
+    SendEmailRequest, SendEmailResponse, MAX_BATCH_SIZE,
+};
+use crate::merge::{MailMerge, MergeReport, Recipient};
+use crate::reqlib::{APIResponse, Response, ValidationError};
+
+#[derive(Debug)]
+pub struct EmailService {
+    pub req_client: ReqClient,
+}
+
+impl EmailService {
+    pub fn new(req_client: ReqClient) -> EmailService {
+        EmailService { req_client }
+    }
+
+    pub fn send<'a>(
+        &self,
+        params: &'a SendEmailRequest<'a>,
+    ) -> Result<APIResponse<SendEmailResponse>, APIResponse<SendEmailResponse>> {
+        self.send_with_meta(params).result
+    }
+
+    pub fn send_with_meta<'a>(
+        &self,
+        params: &'a SendEmailRequest<'a>,
+    ) -> Response<SendEmailResponse> {
+        if let Err(err) = params.validate() {
+            return Response {
+                result: Err(APIResponse::Failure(err.into())),
+                meta: None,
+            };
+        }
+        let req = self
+            .req_client
+            .new_body_request(Method::POST, "emails", Some(params));
+        self.req_client.exec_with_meta(req)
+    }
+
+    pub fn send_batch<'a>(
+        &self,
+        params: &'a [SendEmailRequest<'a>],
+    ) -> Result<APIResponse<SendBatchResponse>, APIResponse<SendBatchResponse>> {
+        self.send_batch_with_meta(params).result
+    }
+
+    pub fn send_batch_with_meta<'a>(
+        &self,
+        params: &'a [SendEmailRequest<'a>],
+    ) -> Response<SendBatchResponse> {
+        if let Err(err) = validate_batch(params) {
+            return Response {
+                result: Err(APIResponse::Failure(err.into())),
+                meta: None,
+            };
+        }
+        let req = self
+            .req_client
+            .new_body_request(Method::POST, "emails/batch", Some(params));
+        let mut response = self.req_client.exec_with_meta(req);
+        check_batch_ids(&mut response, params.len());
+        response
+    }
+
+    // sends one email per recipient through `emails/batch`, MAX_BATCH_SIZE at a time, like
+    // the async `send_merge`
+    pub fn send_merge<V: Serialize, I: IntoIterator<Item = Recipient<V>>>(
+        &self,
+        merge: &MailMerge<'_>,
+        recipients: I,
+    ) -> Result<MergeReport, ValidationError> {
+        let (to, emails) = merge.expand_checked(recipients, |email| {
+            email.validate()?;
+            Ok(email.clone())
+        })?;
+        let mut report = MergeReport::default();
+        for (to, chunk) in to.chunks(MAX_BATCH_SIZE).zip(emails.chunks(MAX_BATCH_SIZE)) {
+            report.record_batch(to, self.send_batch_with_meta(chunk).result);
+        }
+        Ok(report)
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn get<T: Into<String>>(
+        &self,
+        email_id: T,
+    ) -> Result<APIResponse<Email>, APIResponse<Email>> {
+        self.get_with_meta(email_id).result
+    }
+
+    pub fn get_with_meta<T: Into<String>>(&self, email_id: T) -> Response<Email> {
+        let path = format!("emails/{}", email_id.into());
+        let req = self.req_client.new_request(Method::GET, &path);
+        self.req_client.exec_with_meta(req)
+    }
+
+    pub fn list(&self) -> Result<APIResponse<ListEmailsResponse>, APIResponse<ListEmailsResponse>> {
+        self.list_with_meta().result
+    }
+
+    pub fn list_with_meta(&self) -> Response<ListEmailsResponse> {
+        let req = self.req_client.new_request(Method::GET, "emails");
+        self.req_client.exec_with_meta(req)
+    }
+}
@@ -0,0 +1,70 @@
+// the email and domain services without dry runs, interceptors or middleware, which only the
+// async client has. like the async services they return `APIResponse` in both arms of the result
+
+pub mod domains;
+pub mod emails;
+pub mod reqlib;
+
+use http::Method;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use domains::DomainService;
+use emails::EmailService;
+use reqlib::ReqClient;
+
+use crate::reqlib::{APIResponse, Response};
+use crate::secret::ApiKey;
+
+#[derive(Debug)]
+pub struct Client {
+    pub raw_client: ReqClient,
+    pub email_service: EmailService,
+    pub domain_service: DomainService,
+}
+
+impl Client {
+    pub fn new<T: Into<ApiKey>>(api_key: T) -> Client {
+        let api_key = api_key.into();
+        let raw_client = ReqClient::new(api_key.clone());
+        let email_service = EmailService::new(ReqClient::new(api_key.clone()));
+        let domain_service = DomainService::new(ReqClient::new(api_key));
+        Client {
+            raw_client,
+            email_service,
+            domain_service,
+        }
+    }
+
+    // e.g. `client.request::<(), Value>(Method::GET, "api-keys", &[], None)` for endpoints
+    // this crate does not wrap yet
+    pub fn request<Req: Serialize + ?Sized, Resp: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&Req>,
+    ) -> Result<APIResponse<Resp>, APIResponse<Resp>> {
+        self.raw_client.request(method, path, query, body)
+    }
+
+    pub fn request_with_meta<Req: Serialize + ?Sized, Resp: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&Req>,
+    ) -> Response<Resp> {
+        self.raw_client.request_with_meta(method, path, query, body)
+    }
+
+    // reads the key from `RESEND_API_KEY`
+    pub fn from_env() -> Result<Client, std::env::VarError> {
+        ApiKey::from_env().map(Client::new)
+    }
+
+    // reads the key from a file, e.g. a mounted Docker or Kubernetes secret
+    pub fn from_key_file<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Client> {
+        ApiKey::from_file(path).map(Client::new)
+    }
+}
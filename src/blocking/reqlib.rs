@@ -0,0 +1,137 @@
+use http::header::{self, HeaderValue};
+use http::Method;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crate::reqlib::{
+    parse_response, request_headers, APIResponse, Response, ResponseMeta, CONTENT_TYPE,
+    DEFAULT_BASE_URL, USER_AGENT,
+};
+use crate::secret::ApiKey;
+use crate::transport::TransportRequest;
+
+#[derive(Debug)]
+pub struct ReqClient {
+    pub client: reqwest::blocking::Client,
+    pub api_key: ApiKey,
+    pub base_url: url::Url,
+    pub user_agent: String,
+    pub headers: HashMap<String, String>,
+}
+
+impl ReqClient {
+    pub fn new<K: Into<ApiKey>>(api_key: K) -> Self {
+        ReqClient::new_custom(reqwest::blocking::Client::new(), api_key)
+    }
+
+    pub fn new_custom<K: Into<ApiKey>>(http_client: reqwest::blocking::Client, api_key: K) -> Self {
+        ReqClient {
+            client: http_client,
+            api_key: api_key.into(),
+            base_url: url::Url::parse(DEFAULT_BASE_URL).unwrap(),
+            user_agent: USER_AGENT.to_string(),
+            headers: HashMap::new(),
+        }
+    }
+
+    pub fn new_request(&self, method: Method, path: &str) -> TransportRequest {
+        let mut url = self.base_url.clone();
+        url.set_path(path);
+        TransportRequest {
+            method,
+            url,
+            // invalid headers are reported by `exec`, like reqwest reports them on send
+            headers: request_headers(&self.headers, &self.user_agent, &self.api_key)
+                .unwrap_or_default(),
+            body: None,
+        }
+    }
+
+    pub fn new_body_request<T: serde::Serialize + ?Sized>(
+        &self,
+        method: Method,
+        path: &str,
+        json: Option<&T>,
+    ) -> TransportRequest {
+        let mut req = self.new_request(method, path);
+        // update request body
+        if let Some(json) = json {
+            req.headers
+                .insert(header::CONTENT_TYPE, HeaderValue::from_static(CONTENT_TYPE));
+            req.body = Some(serde_json::to_vec(json).unwrap());
+        }
+        req
+    }
+
+    // calls an endpoint that has no service yet, with the same headers and error handling as
+    // the built-in services
+    pub fn request<Req: serde::Serialize + ?Sized, Resp: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&Req>,
+    ) -> Result<APIResponse<Resp>, APIResponse<Resp>> {
+        self.request_with_meta(method, path, query, body).result
+    }
+
+    pub fn request_with_meta<Req: serde::Serialize + ?Sized, Resp: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&Req>,
+    ) -> Response<Resp> {
+        let mut req = self.new_body_request(method, path, body);
+        if !query.is_empty() {
+            req.url.query_pairs_mut().extend_pairs(query);
+        }
+        self.exec_with_meta(req)
+    }
+
+    pub fn exec<T: DeserializeOwned>(
+        &self,
+        req: TransportRequest,
+    ) -> Result<APIResponse<T>, APIResponse<T>> {
+        self.exec_with_meta(req).result
+    }
+
+    pub fn exec_with_meta<T: DeserializeOwned>(&self, req: TransportRequest) -> Response<T> {
+        if let Err(err) = request_headers(&self.headers, &self.user_agent, &self.api_key) {
+            return Response {
+                result: Err(APIResponse::Failure(err.into())),
+                meta: None,
+            };
+        }
+        let mut builder = self
+            .client
+            .request(req.method, req.url)
+            .headers(req.headers);
+        if let Some(body) = req.body {
+            builder = builder.body(body);
+        }
+        let started = Instant::now();
+        let response = match builder.send() {
+            Ok(response) => response,
+            Err(err) => {
+                return Response {
+                    result: Err(APIResponse::Failure(err.into())),
+                    meta: None,
+                }
+            }
+        };
+        let status = response.status();
+        let headers = response.headers().clone();
+        match response.bytes() {
+            Ok(body) => Response {
+                result: parse_response(status, &body),
+                meta: Some(ResponseMeta::new(status, headers, started.elapsed())),
+            },
+            Err(err) => Response {
+                result: Err(APIResponse::Failure(err.into())),
+                meta: None,
+            },
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use http::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::reqlib::{Error, ReqClient};
+use crate::secret::redact_addresses;
+use crate::transport::{Transport, TransportFuture, TransportRequest, TransportResponse};
+
+const REDACTED: &str = "[REDACTED]";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    pub headers: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub headers: BTreeMap<String, String>,
+    pub body: serde_json::Value,
+}
+
+enum Mode {
+    Record(Arc<dyn Transport>),
+    Replay,
+}
+
+// records real interactions to a JSON file, or serves them back without touching the network
+pub struct CassetteTransport {
+    mode: Mode,
+    path: PathBuf,
+    cassette: Mutex<Cassette>,
+    // interactions already served during replay, each one is only served once
+    used: Mutex<Vec<bool>>,
+}
+
+impl CassetteTransport {
+    pub fn record<P: Into<PathBuf>>(inner: Arc<dyn Transport>, path: P) -> Self {
+        CassetteTransport {
+            mode: Mode::Record(inner),
+            path: path.into(),
+            cassette: Mutex::new(Cassette::default()),
+            used: Mutex::new(vec![]),
+        }
+    }
+
+    pub fn replay<P: Into<PathBuf>>(path: P) -> io::Result<Self> {
+        let path = path.into();
+        let cassette: Cassette = serde_json::from_slice(&fs::read(&path)?)?;
+        let used = vec![false; cassette.interactions.len()];
+        Ok(CassetteTransport {
+            mode: Mode::Replay,
+            path,
+            cassette: Mutex::new(cassette),
+            used: Mutex::new(used),
+        })
+    }
+
+    pub fn cassette(&self) -> Cassette {
+        self.cassette.lock().unwrap().clone()
+    }
+
+    fn save(&self, interaction: Interaction) -> Result<(), Error> {
+        let mut cassette = self.cassette.lock().unwrap();
+        cassette.interactions.push(interaction);
+        // written after every interaction so nothing is lost if the test panics later on
+        let json = serde_json::to_vec_pretty(&*cassette).unwrap();
+        fs::write(&self.path, json).map_err(|err| Error::Transport(Box::new(err)))
+    }
+
+    fn find(&self, request: &RecordedRequest) -> Result<TransportResponse, Error> {
+        let cassette = self.cassette.lock().unwrap();
+        let mut used = self.used.lock().unwrap();
+        let index = (0..cassette.interactions.len())
+            .find(|&index| !used[index] && matches(&cassette.interactions[index].request, request))
+            .ok_or_else(|| {
+                Error::Transport(
+                    format!(
+                        "no unused interaction in {} matches {} {}",
+                        self.path.display(),
+                        request.method,
+                        request.path
+                    )
+                    .into(),
+                )
+            })?;
+        used[index] = true;
+        to_response(&cassette.interactions[index].response)
+    }
+}
+
+impl Transport for CassetteTransport {
+    fn send(&self, request: TransportRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let recorded = record_request(&request);
+            match &self.mode {
+                Mode::Replay => self.find(&recorded),
+                Mode::Record(inner) => {
+                    let response = inner.send(request).await?;
+                    self.save(Interaction {
+                        request: recorded,
+                        response: record_response(&response),
+                    })?;
+                    Ok(response)
+                }
+            }
+        })
+    }
+}
+
+impl ReqClient {
+    // wraps the current transport; share one `CassetteTransport` via `Client::with_transport`
+    // when several services should record into the same file
+    pub fn record_cassette<P: Into<PathBuf>>(&mut self, path: P) {
+        self.transport = Arc::new(CassetteTransport::record(self.transport.clone(), path));
+    }
+
+    pub fn replay_cassette<P: Into<PathBuf>>(&mut self, path: P) -> io::Result<()> {
+        self.transport = Arc::new(CassetteTransport::replay(path)?);
+        Ok(())
+    }
+}
+
+fn matches(recorded: &RecordedRequest, request: &RecordedRequest) -> bool {
+    recorded.method == request.method
+        && recorded.path == request.path
+        && recorded.query == request.query
+        && recorded.body == request.body
+}
+
+fn record_request(request: &TransportRequest) -> RecordedRequest {
+    RecordedRequest {
+        method: request.method.to_string(),
+        path: request.url.path().to_string(),
+        query: request.url.query().map(String::from),
+        headers: record_headers(&request.headers),
+        body: request.body.as_deref().map(record_body),
+    }
+}
+
+fn record_response(response: &TransportResponse) -> RecordedResponse {
+    RecordedResponse {
+        status: response.status.as_u16(),
+        headers: record_headers(&response.headers),
+        body: record_body(&response.body),
+    }
+}
+
+fn record_headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if name == AUTHORIZATION {
+                format!("Bearer {}", REDACTED)
+            } else {
+                redact_addresses(&String::from_utf8_lossy(value.as_bytes()))
+            };
+            (name.to_string(), value)
+        })
+        .collect()
+}
+
+// JSON bodies are stored as JSON so cassettes stay readable and diffable
+fn record_body(body: &[u8]) -> serde_json::Value {
+    match serde_json::from_slice::<serde_json::Value>(body) {
+        Ok(mut value) => {
+            redact_value(&mut value);
+            value
+        }
+        Err(_) => serde_json::Value::String(redact_addresses(&String::from_utf8_lossy(body))),
+    }
+}
+
+fn to_response(recorded: &RecordedResponse) -> Result<TransportResponse, Error> {
+    let invalid = |err: String| Error::Transport(err.into());
+    let mut headers = HeaderMap::new();
+    for (name, value) in recorded.headers.iter() {
+        headers.insert(
+            HeaderName::from_bytes(name.as_bytes()).map_err(|err| invalid(err.to_string()))?,
+            HeaderValue::from_str(value).map_err(|err| invalid(err.to_string()))?,
+        );
+    }
+    let body = match &recorded.body {
+        serde_json::Value::String(body) => body.as_bytes().to_vec(),
+        body => serde_json::to_vec(body).unwrap(),
+    };
+    Ok(TransportResponse {
+        status: StatusCode::from_u16(recorded.status).map_err(|err| invalid(err.to_string()))?,
+        headers,
+        body,
+    })
+}
+
+fn redact_value(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::String(s) => *s = redact_addresses(s),
+        serde_json::Value::Array(values) => values.iter_mut().for_each(redact_value),
+        serde_json::Value::Object(map) => map.values_mut().for_each(redact_value),
+        _ => {}
+    }
+}
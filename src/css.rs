@@ -0,0 +1,143 @@
+use css_inline::CSSInliner;
+
+use crate::emails::{SendEmailRequest, SendInterceptor};
+use crate::reqlib::ValidationError;
+
+// moves `<style>` rules into `style` attributes since most mail clients ignore style blocks.
+// media queries and other at-rules cannot be inlined and stay behind in a `<style>` block
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CssInliner {
+    // minifies the inlined CSS and collapses whitespace and comments in the html
+    pub minify: bool,
+}
+
+impl CssInliner {
+    pub fn new() -> Self {
+        CssInliner::default()
+    }
+
+    pub fn minify(mut self) -> Self {
+        self.minify = true;
+        self
+    }
+
+    pub fn inline(&self, html: &str) -> Result<String, ValidationError> {
+        let options = CSSInliner::options()
+            .keep_at_rules(true)
+            .minify_css(self.minify)
+            .load_remote_stylesheets(false);
+        let html = CSSInliner::new(options).inline(html).map_err(|err| {
+            ValidationError::new("html", format!("could not inline CSS: {}", err))
+        })?;
+        Ok(match self.minify {
+            true => minify_html(&html),
+            false => html,
+        })
+    }
+}
+
+// add it as an interceptor so the inlined html is what the size check sees
+impl SendInterceptor for CssInliner {
+    fn intercept(&self, email: &mut SendEmailRequest<'_>) -> Result<(), ValidationError> {
+        if let Some(html) = &email.html {
+            email.html = Some(self.inline(html)?);
+        }
+        Ok(())
+    }
+}
+
+// elements whose content is kept exactly as written
+const RAW_ELEMENTS: [&str; 4] = ["pre", "textarea", "script", "style"];
+
+// collapses runs of whitespace into one space and drops comments. conditional comments are kept
+// since Outlook relies on them, and so are quoted attribute values and raw elements like `<pre>`
+fn minify_html(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    let mut space = false;
+    while let Some(c) = rest.chars().next() {
+        if c.is_ascii_whitespace() {
+            space = true;
+            rest = &rest[1..];
+            continue;
+        }
+        let comment = rest.strip_prefix("<!--");
+        if comment
+            .is_some_and(|comment| !comment.starts_with("[if") && !comment.starts_with("<![endif"))
+        {
+            // dropped, the whitespace around it collapses into one space
+            rest = rest.find("-->").map_or("", |end| &rest[end + 3..]);
+            continue;
+        }
+        if std::mem::take(&mut space) && !out.is_empty() {
+            out.push(' ');
+        }
+        if comment.is_some() {
+            let end = rest.find("-->").map_or(rest.len(), |end| end + 3);
+            out.push_str(&rest[..end]);
+            rest = &rest[end..];
+        } else if c == '<' {
+            let end = tag_end(rest);
+            let tag = &rest[..end];
+            push_tag(&mut out, tag);
+            rest = &rest[end..];
+            if let Some(name) = raw_element(tag) {
+                let close = rest
+                    .to_ascii_lowercase()
+                    .find(&format!("</{}", name))
+                    .unwrap_or(rest.len());
+                out.push_str(&rest[..close]);
+                rest = &rest[close..];
+            }
+        } else {
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    out
+}
+
+// the byte after the `>` that closes the tag at the start of `html`, ignoring quoted `>`
+fn tag_end(html: &str) -> usize {
+    let mut quote = None;
+    for (index, c) in html.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if open == c => quote = None,
+            (None, '>') => return index + 1,
+            _ => {}
+        }
+    }
+    html.len()
+}
+
+fn push_tag(out: &mut String, tag: &str) {
+    let mut quote = None;
+    let mut space = false;
+    for c in tag.chars() {
+        if quote.is_none() && c.is_ascii_whitespace() {
+            space = true;
+            continue;
+        }
+        if std::mem::take(&mut space) && c != '>' {
+            out.push(' ');
+        }
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if open == c => quote = None,
+            _ => {}
+        }
+        out.push(c);
+    }
+}
+
+// the name of an opening `pre`, `textarea`, `script` or `style` tag
+fn raw_element(tag: &str) -> Option<&'static str> {
+    let name = tag
+        .strip_prefix('<')?
+        .split(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/')
+        .next()?;
+    RAW_ELEMENTS
+        .into_iter()
+        .find(|raw| raw.eq_ignore_ascii_case(name))
+}
@@ -0,0 +1,263 @@
+use std::future::Future;
+
+use crate::domains::{DnsRecord, DnsRecordType, DomainDetails};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolvedRecord {
+    Mx { priority: u16, exchange: String },
+    Txt(String),
+    Cname(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolveError {
+    pub message: String,
+}
+
+pub trait DnsResolver {
+    // an empty list means the name resolved but has no records of the requested type
+    fn lookup(
+        &self,
+        name: &str,
+        record_type: &DnsRecordType,
+    ) -> impl Future<Output = Result<Vec<ResolvedRecord>, ResolveError>> + Send;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordCheck {
+    Ok,
+    Missing,
+    Mismatch {
+        found: Vec<String>,
+    },
+    PriorityMismatch {
+        expected: Option<u16>,
+        found: Vec<u16>,
+    },
+    LookupFailed(ResolveError),
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordReport {
+    pub record: DnsRecord,
+    pub fully_qualified_name: String,
+    pub check: RecordCheck,
+}
+
+#[derive(Debug, Clone)]
+pub struct DomainReport {
+    pub domain: String,
+    pub records: Vec<RecordReport>,
+}
+
+impl DomainReport {
+    pub fn is_ok(&self) -> bool {
+        self.records
+            .iter()
+            .all(|report| report.check == RecordCheck::Ok)
+    }
+
+    pub fn problems(&self) -> impl Iterator<Item = &RecordReport> {
+        self.records
+            .iter()
+            .filter(|report| report.check != RecordCheck::Ok)
+    }
+}
+
+pub async fn check_domain<R: DnsResolver>(resolver: &R, domain: &DomainDetails) -> DomainReport {
+    let mut records = Vec::with_capacity(domain.records.len());
+    for record in domain.records.iter() {
+        records.push(check_record(resolver, &domain.name, record).await);
+    }
+    DomainReport {
+        domain: domain.name.clone(),
+        records,
+    }
+}
+
+pub async fn check_record<R: DnsResolver>(
+    resolver: &R,
+    domain: &str,
+    record: &DnsRecord,
+) -> RecordReport {
+    let fully_qualified_name = record.fully_qualified_name(domain);
+    let check = match resolver.lookup(&fully_qualified_name, &record.r#type).await {
+        Ok(resolved) => compare(record, &resolved),
+        Err(err) => RecordCheck::LookupFailed(err),
+    };
+    RecordReport {
+        record: record.clone(),
+        fully_qualified_name,
+        check,
+    }
+}
+
+fn compare(record: &DnsRecord, resolved: &[ResolvedRecord]) -> RecordCheck {
+    match record.r#type {
+        DnsRecordType::Mx => {
+            let expected = normalize_host(&record.value);
+            let exchanges: Vec<(u16, String)> = resolved
+                .iter()
+                .filter_map(|resolved| match resolved {
+                    ResolvedRecord::Mx { priority, exchange } => {
+                        Some((*priority, normalize_host(exchange)))
+                    }
+                    _ => None,
+                })
+                .collect();
+            let matching: Vec<u16> = exchanges
+                .iter()
+                .filter(|(_, exchange)| *exchange == expected)
+                .map(|(priority, _)| *priority)
+                .collect();
+            if exchanges.is_empty() {
+                RecordCheck::Missing
+            } else if matching.is_empty() {
+                RecordCheck::Mismatch {
+                    found: exchanges
+                        .into_iter()
+                        .map(|(priority, exchange)| format!("{} {}", priority, exchange))
+                        .collect(),
+                }
+            } else if record.priority.is_some_and(|p| !matching.contains(&p)) {
+                RecordCheck::PriorityMismatch {
+                    expected: record.priority,
+                    found: matching,
+                }
+            } else {
+                RecordCheck::Ok
+            }
+        }
+        DnsRecordType::Txt => {
+            let expected = normalize_txt(&record.value);
+            let values: Vec<String> = resolved
+                .iter()
+                .filter_map(|resolved| match resolved {
+                    ResolvedRecord::Txt(value) => Some(normalize_txt(value)),
+                    _ => None,
+                })
+                .collect();
+            if values.is_empty() {
+                RecordCheck::Missing
+            } else if values.contains(&expected) {
+                RecordCheck::Ok
+            } else {
+                RecordCheck::Mismatch { found: values }
+            }
+        }
+        DnsRecordType::Cname => {
+            let expected = normalize_host(&record.value);
+            let targets: Vec<String> = resolved
+                .iter()
+                .filter_map(|resolved| match resolved {
+                    ResolvedRecord::Cname(target) => Some(normalize_host(target)),
+                    _ => None,
+                })
+                .collect();
+            if targets.is_empty() {
+                RecordCheck::Missing
+            } else if targets.contains(&expected) {
+                RecordCheck::Ok
+            } else {
+                RecordCheck::Mismatch { found: targets }
+            }
+        }
+    }
+}
+
+fn normalize_host(value: &str) -> String {
+    value.trim().trim_end_matches('.').to_ascii_lowercase()
+}
+
+fn normalize_txt(value: &str) -> String {
+    // providers often display TXT values quoted and split into 255 byte chunks
+    let value = value.trim();
+    if value.starts_with('"') && value.ends_with('"') && value.len() >= 2 {
+        value[1..value.len() - 1]
+            .split("\" \"")
+            .collect::<Vec<&str>>()
+            .join("")
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(feature = "hickory")]
+pub use self::hickory::HickoryResolver;
+
+#[cfg(feature = "hickory")]
+mod hickory {
+    use std::future::Future;
+
+    use hickory_resolver::proto::rr::{RData, RecordType};
+    use hickory_resolver::TokioResolver;
+
+    use super::{DnsResolver, ResolveError, ResolvedRecord};
+    use crate::domains::DnsRecordType;
+
+    pub struct HickoryResolver {
+        pub resolver: TokioResolver,
+    }
+
+    impl HickoryResolver {
+        pub fn new(resolver: TokioResolver) -> Self {
+            HickoryResolver { resolver }
+        }
+
+        pub fn from_system_conf() -> Result<Self, ResolveError> {
+            let resolver = TokioResolver::builder_tokio()
+                .and_then(|builder| builder.build())
+                .map_err(|err| ResolveError {
+                    message: err.to_string(),
+                })?;
+            Ok(HickoryResolver { resolver })
+        }
+    }
+
+    impl DnsResolver for HickoryResolver {
+        fn lookup(
+            &self,
+            name: &str,
+            record_type: &DnsRecordType,
+        ) -> impl Future<Output = Result<Vec<ResolvedRecord>, ResolveError>> + Send {
+            let record_type = match record_type {
+                DnsRecordType::Mx => RecordType::MX,
+                DnsRecordType::Txt => RecordType::TXT,
+                DnsRecordType::Cname => RecordType::CNAME,
+            };
+            // query the absolute name so search domains are never appended
+            let name = format!("{}.", name.trim_end_matches('.'));
+            async move {
+                let lookup = match self.resolver.lookup(name, record_type).await {
+                    Ok(lookup) => lookup,
+                    Err(err) if err.is_no_records_found() || err.is_nx_domain() => {
+                        return Ok(vec![])
+                    }
+                    Err(err) => {
+                        return Err(ResolveError {
+                            message: err.to_string(),
+                        })
+                    }
+                };
+                Ok(lookup
+                    .answers()
+                    .iter()
+                    .filter_map(|record| match &record.data {
+                        RData::MX(mx) => Some(ResolvedRecord::Mx {
+                            priority: mx.preference,
+                            exchange: mx.exchange.to_string(),
+                        }),
+                        RData::TXT(txt) => Some(ResolvedRecord::Txt(
+                            txt.txt_data
+                                .iter()
+                                .map(|chunk| String::from_utf8_lossy(chunk))
+                                .collect(),
+                        )),
+                        RData::CNAME(cname) => Some(ResolvedRecord::Cname(cname.0.to_string())),
+                        _ => None,
+                    })
+                    .collect())
+            }
+        }
+    }
+}
@@ -1,9 +1,11 @@
-use reqwest::Method;
-use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr, time::Duration};
 
-use crate::reqlib::{APIResponse, ReqClient};
+use http::Method;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+use crate::reqlib::{APIResponse, ReqClient, Response};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum DnsRecordType {
     Mx,
@@ -11,14 +13,33 @@ pub enum DnsRecordType {
     Txt,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+impl DnsRecordType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DnsRecordType::Mx => "MX",
+            DnsRecordType::Cname => "CNAME",
+            DnsRecordType::Txt => "TXT",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum EmailDnsRecord {
     Spf,
     Dkim,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+impl EmailDnsRecord {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmailDnsRecord::Spf => "SPF",
+            EmailDnsRecord::Dkim => "DKIM",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum DomainStatus {
     Pending,
@@ -28,11 +49,112 @@ pub enum DomainStatus {
     NotStarted,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Region {
+    UsEast1,
+    EuWest1,
+    SaEast1,
+    ApNortheast1,
+    // regions added by Resend after this version of the crate
+    Other(String),
+}
+
+impl Region {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Region::UsEast1 => "us-east-1",
+            Region::EuWest1 => "eu-west-1",
+            Region::SaEast1 => "sa-east-1",
+            Region::ApNortheast1 => "ap-northeast-1",
+            Region::Other(region) => region,
+        }
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Region {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Region::from(s))
+    }
+}
+
+impl From<&str> for Region {
+    fn from(value: &str) -> Self {
+        match value {
+            "us-east-1" => Region::UsEast1,
+            "eu-west-1" => Region::EuWest1,
+            "sa-east-1" => Region::SaEast1,
+            "ap-northeast-1" => Region::ApNortheast1,
+            other => Region::Other(other.to_string()),
+        }
+    }
+}
+
+impl From<String> for Region {
+    fn from(value: String) -> Self {
+        Region::from(&value[..])
+    }
+}
+
+impl Serialize for Region {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Region {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Region::from)
+    }
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct CreateDomainRequest {
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub region: Option<String>,
+    pub region: Option<Region>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_return_path: Option<String>,
+}
+
+impl CreateDomainRequest {
+    pub fn builder<T: Into<String>>(name: T) -> CreateDomainRequestBuilder {
+        CreateDomainRequestBuilder {
+            request: CreateDomainRequest {
+                name: name.into(),
+                region: None,
+                custom_return_path: None,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateDomainRequestBuilder {
+    request: CreateDomainRequest,
+}
+
+impl CreateDomainRequestBuilder {
+    pub fn region(mut self, region: Region) -> Self {
+        self.request.region = Some(region);
+        self
+    }
+
+    pub fn custom_return_path<T: Into<String>>(mut self, custom_return_path: T) -> Self {
+        self.request.custom_return_path = Some(custom_return_path.into());
+        self
+    }
+
+    pub fn build(self) -> CreateDomainRequest {
+        self.request
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -46,13 +168,48 @@ pub struct DnsRecord {
     pub priority: Option<u16>,
 }
 
+impl DnsRecord {
+    // record names are returned relative to the zone the domain is in, e.g. `resend._domainkey`
+    // for `example.com` but `resend._domainkey.mail` for `mail.example.com`
+    pub fn fully_qualified_name(&self, domain: &str) -> String {
+        let name = self.name.trim_end_matches('.');
+        let domain = domain.trim_end_matches('.');
+        if name.is_empty() || name == "@" {
+            return domain.to_string();
+        }
+        if name.eq_ignore_ascii_case(domain)
+            || name
+                .to_ascii_lowercase()
+                .ends_with(&format!(".{}", domain.to_ascii_lowercase()))
+        {
+            return name.to_string();
+        }
+        // the trailing labels of the name repeat the leading labels of a subdomain, the zone
+        // itself keeps at least two labels
+        let name_labels: Vec<&str> = name.split('.').collect();
+        let domain_labels: Vec<&str> = domain.split('.').collect();
+        let longest = name_labels.len().min(domain_labels.len().saturating_sub(2));
+        let overlap = (1..=longest).rev().find(|&count| {
+            name_labels[name_labels.len() - count..]
+                .iter()
+                .zip(&domain_labels[..count])
+                .all(|(a, b)| a.eq_ignore_ascii_case(b))
+        });
+        match overlap {
+            Some(count) => format!("{}.{}", name, domain_labels[count..].join(".")),
+            None => format!("{}.{}", name, domain),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[allow(non_snake_case)]
 pub struct CreateDomainResponse {
     pub id: String,
     pub name: String,
     pub created_at: String,
     pub status: DomainStatus,
-    pub region: String,
+    pub region: Region,
     // this property is not snake case in the Resend API for some reason
     pub dnsProvider: String,
 }
@@ -64,7 +221,7 @@ pub struct DomainDetails {
     pub name: String,
     pub created_at: String,
     pub status: DomainStatus,
-    pub region: String,
+    pub region: Region,
     pub records: Vec<DnsRecord>,
 }
 
@@ -74,7 +231,7 @@ pub struct DomainSummary {
     pub name: String,
     pub created_at: String,
     pub status: DomainStatus,
-    pub region: String,
+    pub region: Region,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -95,6 +252,102 @@ pub struct DeleteDomainResponse {
     pub deleted: bool,
 }
 
+#[derive(Debug, Clone)]
+pub struct WaitOptions {
+    // delay before the first re-poll, doubled (up to `max_interval`) after every attempt
+    pub initial_interval: Duration,
+    pub max_interval: Duration,
+    // total time to keep polling before giving up
+    pub timeout: Duration,
+}
+
+impl Default for WaitOptions {
+    fn default() -> Self {
+        WaitOptions {
+            initial_interval: Duration::from_secs(2),
+            max_interval: Duration::from_secs(30),
+            timeout: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum VerificationOutcome {
+    Verified(DomainDetails),
+    Failed {
+        domain: DomainDetails,
+        failing_records: Vec<DnsRecord>,
+    },
+    // holds the last domain state seen before the deadline passed
+    TimedOut(DomainDetails),
+}
+
+// the polling schedule of `wait_until_verified`, shared with the blocking client so both only
+// differ in how they fetch the domain and sleep
+pub(crate) struct Poller {
+    interval: Duration,
+    max_interval: Duration,
+    timeout: Duration,
+}
+
+pub(crate) enum PollStep {
+    // what `wait_until_verified` returns
+    Finish(Result<VerificationOutcome, APIResponse<DomainDetails>>),
+    Sleep(Duration),
+}
+
+impl Poller {
+    pub(crate) fn new(options: &WaitOptions) -> Self {
+        Poller {
+            interval: options.initial_interval,
+            max_interval: options.max_interval,
+            timeout: options.timeout,
+        }
+    }
+
+    // `elapsed` is the time since the first poll started
+    pub(crate) fn step<F: FnMut(&[DnsRecord])>(
+        &mut self,
+        response: Result<APIResponse<DomainDetails>, APIResponse<DomainDetails>>,
+        elapsed: Duration,
+        on_progress: &mut F,
+    ) -> PollStep {
+        let domain = match response {
+            Ok(APIResponse::Success(domain)) => domain,
+            Ok(other) | Err(other) => return PollStep::Finish(Err(other)),
+        };
+        on_progress(&domain.records);
+
+        if let Some(outcome) = settle(&domain) {
+            return PollStep::Finish(Ok(outcome));
+        }
+        if elapsed >= self.timeout {
+            return PollStep::Finish(Ok(VerificationOutcome::TimedOut(domain)));
+        }
+        let delay = self.interval.min(self.timeout - elapsed);
+        self.interval = (self.interval * 2).min(self.max_interval);
+        PollStep::Sleep(delay)
+    }
+}
+
+// `None` while the domain is still pending, not started or temporarily failing
+fn settle(domain: &DomainDetails) -> Option<VerificationOutcome> {
+    match domain.status {
+        DomainStatus::Verified => Some(VerificationOutcome::Verified(domain.clone())),
+        DomainStatus::Failed => Some(VerificationOutcome::Failed {
+            domain: domain.clone(),
+            failing_records: domain
+                .records
+                .iter()
+                .filter(|record| record.status != DomainStatus::Verified)
+                .cloned()
+                .collect(),
+        }),
+        _ => None,
+    }
+}
+
+#[derive(Debug)]
 pub struct DomainService {
     pub req_client: ReqClient,
 }
@@ -104,52 +357,103 @@ impl DomainService {
         DomainService { req_client }
     }
 
-    pub async fn create<'a>(
+    pub async fn create(
         &self,
-        params: &'a CreateDomainRequest,
+        params: &CreateDomainRequest,
     ) -> Result<APIResponse<CreateDomainResponse>, APIResponse<CreateDomainResponse>> {
+        self.create_with_meta(params).await.result
+    }
+
+    pub async fn create_with_meta(
+        &self,
+        params: &CreateDomainRequest,
+    ) -> Response<CreateDomainResponse> {
         let req = self
             .req_client
             .new_body_request(Method::POST, "domains", Some(params));
-        let result = self.req_client.exec::<CreateDomainResponse>(req).await;
-        result
+        self.req_client.exec_with_meta(req).await
     }
 
-    pub async fn verify<'a, T: Into<String>>(
+    pub async fn verify<T: Into<String>>(
         &self,
         domain_id: T,
     ) -> Result<APIResponse<VerifyDomainResponse>, APIResponse<VerifyDomainResponse>> {
+        self.verify_with_meta(domain_id).await.result
+    }
+
+    pub async fn verify_with_meta<T: Into<String>>(
+        &self,
+        domain_id: T,
+    ) -> Response<VerifyDomainResponse> {
         let path = format!("domains/{}", domain_id.into());
-        let req = self.req_client.new_request(Method::POST, &path[..]);
-        let result = self.req_client.exec::<VerifyDomainResponse>(req).await;
-        result
+        let req = self.req_client.new_request(Method::POST, &path);
+        self.req_client.exec_with_meta(req).await
     }
 
-    pub async fn get<'a, T: Into<String>>(
+    pub async fn get<T: Into<String>>(
         &self,
         domain_id: T,
     ) -> Result<APIResponse<DomainDetails>, APIResponse<DomainDetails>> {
+        self.get_with_meta(domain_id).await.result
+    }
+
+    pub async fn get_with_meta<T: Into<String>>(&self, domain_id: T) -> Response<DomainDetails> {
         let path = format!("domains/{}", domain_id.into());
-        let req = self.req_client.new_request(Method::GET, &path[..]);
-        let result = self.req_client.exec::<DomainDetails>(req).await;
-        result
+        let req = self.req_client.new_request(Method::GET, &path);
+        self.req_client.exec_with_meta(req).await
     }
 
-    pub async fn list<'a>(
+    pub async fn list(
         &self,
     ) -> Result<APIResponse<ListDomainsResponse>, APIResponse<ListDomainsResponse>> {
+        self.list_with_meta().await.result
+    }
+
+    pub async fn list_with_meta(&self) -> Response<ListDomainsResponse> {
         let req = self.req_client.new_request(Method::GET, "domains");
-        let result = self.req_client.exec::<ListDomainsResponse>(req).await;
-        result
+        self.req_client.exec_with_meta(req).await
     }
 
-    pub async fn delete<'a, T: Into<String>>(
+    pub async fn delete<T: Into<String>>(
         &self,
         domain_id: T,
     ) -> Result<APIResponse<DeleteDomainResponse>, APIResponse<DeleteDomainResponse>> {
+        self.delete_with_meta(domain_id).await.result
+    }
+
+    pub async fn delete_with_meta<T: Into<String>>(
+        &self,
+        domain_id: T,
+    ) -> Response<DeleteDomainResponse> {
         let path = format!("domains/{}", domain_id.into());
-        let req = self.req_client.new_request(Method::DELETE, &path[..]);
-        let result = self.req_client.exec::<DeleteDomainResponse>(req).await;
-        result
+        let req = self.req_client.new_request(Method::DELETE, &path);
+        self.req_client.exec_with_meta(req).await
+    }
+
+    pub async fn wait_until_verified<T: Into<String>>(
+        &self,
+        domain_id: T,
+        options: &WaitOptions,
+    ) -> Result<VerificationOutcome, APIResponse<DomainDetails>> {
+        self.wait_until_verified_with_progress(domain_id, options, |_| {})
+            .await
+    }
+
+    pub async fn wait_until_verified_with_progress<T: Into<String>, F: FnMut(&[DnsRecord])>(
+        &self,
+        domain_id: T,
+        options: &WaitOptions,
+        mut on_progress: F,
+    ) -> Result<VerificationOutcome, APIResponse<DomainDetails>> {
+        let domain_id = domain_id.into();
+        let started = tokio::time::Instant::now();
+        let mut poller = Poller::new(options);
+        loop {
+            let response = self.get(domain_id.clone()).await;
+            match poller.step(response, started.elapsed(), &mut on_progress) {
+                PollStep::Finish(result) => return result,
+                PollStep::Sleep(delay) => tokio::time::sleep(delay).await,
+            }
+        }
     }
 }
@@ -0,0 +1,67 @@
+use std::sync::Mutex;
+
+use crate::emails::{SendEmailRequest, SendEmailResponse};
+
+#[derive(Debug, Clone)]
+pub struct DryRunEmail {
+    pub id: String,
+    // the JSON body that would have been posted to the API
+    pub payload: serde_json::Value,
+}
+
+#[derive(Debug, Default)]
+pub struct DryRun {
+    // recipient domains that are still delivered for real, e.g. a QA inbox domain
+    pub allowed_domains: Vec<String>,
+    emails: Mutex<Vec<DryRunEmail>>,
+}
+
+impl DryRun {
+    pub fn new() -> Self {
+        DryRun::default()
+    }
+
+    pub fn allow_domain<T: Into<String>>(mut self, domain: T) -> Self {
+        self.allowed_domains
+            .push(domain.into().to_ascii_lowercase());
+        self
+    }
+
+    pub fn emails(&self) -> Vec<DryRunEmail> {
+        self.emails.lock().unwrap().clone()
+    }
+
+    // an email only passes through when every recipient is on the allow-list
+    pub fn passes_through(&self, params: &SendEmailRequest) -> bool {
+        !self.allowed_domains.is_empty()
+            && params.recipients().all(|recipient| {
+                recipient_domain(recipient).is_some_and(|domain| {
+                    self.allowed_domains
+                        .iter()
+                        .any(|allowed| allowed.eq_ignore_ascii_case(&domain))
+                })
+            })
+    }
+
+    pub(crate) fn record(&self, params: &SendEmailRequest) -> SendEmailResponse {
+        let mut emails = self.emails.lock().unwrap();
+        let id = format!("dry-run-{:012}", emails.len() + 1);
+        emails.push(DryRunEmail {
+            id: id.clone(),
+            payload: serde_json::to_value(params).unwrap(),
+        });
+        SendEmailResponse { id }
+    }
+}
+
+// handles both `user@domain.com` and `Name <user@domain.com>`
+pub(crate) fn recipient_domain(recipient: &str) -> Option<String> {
+    let address = match (recipient.rfind('<'), recipient.rfind('>')) {
+        (Some(start), Some(end)) if start < end => &recipient[start + 1..end],
+        _ => recipient,
+    };
+    address
+        .trim()
+        .rsplit_once('@')
+        .map(|(_, domain)| domain.to_ascii_lowercase())
+}
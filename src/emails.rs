@@ -1,9 +1,19 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
 
-use reqwest::Method;
+use http::Method;
 use serde::{Deserialize, Serialize};
 
-use crate::reqlib::{APIResponse, ReqClient};
+use crate::dry_run::DryRun;
+use crate::merge::{MailMerge, MergeReport, Recipient};
+use crate::reqlib::{APIResponse, Error, ReqClient, Response, ValidationError};
+
+// limits documented by Resend
+const MAX_RECIPIENTS: usize = 50;
+pub(crate) const MAX_BATCH_SIZE: usize = 100;
+const MAX_EMAIL_SIZE: usize = 40 * 1024 * 1024;
 
 #[derive(Debug, Serialize, Clone)]
 pub struct Tag {
@@ -11,11 +21,141 @@ pub struct Tag {
     value: String,
 }
 
-#[derive(Debug, Serialize, Clone)]
+impl Tag {
+    pub fn new<N: Into<String>, V: Into<String>>(name: N, value: V) -> Self {
+        Tag {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Attachment {
     content: Vec<u8>,
     filename: String,
     path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_type: Option<String>,
+    // the html shows the attachment inline by referencing it as `cid:<content_id>`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_id: Option<String>,
+}
+
+impl Attachment {
+    pub fn new<F: Into<String>, C: Into<Vec<u8>>>(filename: F, content: C) -> Self {
+        Attachment {
+            content: content.into(),
+            filename: filename.into(),
+            path: None,
+            content_type: None,
+            content_id: None,
+        }
+    }
+
+    // Resend downloads the content from `path` when the email is sent
+    pub fn from_path<F: Into<String>, P: Into<String>>(filename: F, path: P) -> Self {
+        Attachment {
+            path: Some(path.into()),
+            ..Attachment::new(filename, vec![])
+        }
+    }
+
+    pub fn with_content_type<T: Into<String>>(mut self, content_type: T) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    pub fn with_content_id<T: Into<String>>(mut self, content_id: T) -> Self {
+        self.content_id = Some(content_id.into());
+        self
+    }
+
+    pub fn content(&self) -> &[u8] {
+        &self.content
+    }
+
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    pub fn content_id(&self) -> Option<&str> {
+        self.content_id.as_deref()
+    }
+}
+
+// content for `SendEmailRequest::set_body`, anything besides html and text is compiled first.
+// features add variants, so matches need a wildcard arm
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EmailBody {
+    Html(String),
+    Text(String),
+    #[cfg(feature = "mjml")]
+    Mjml(String),
+    // fills both `html` and `text`
+    #[cfg(feature = "markdown")]
+    Markdown(crate::markdown::Markdown),
+}
+
+// why `SendEmailRequest::set_body` could not fill the email
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BodyError {
+    Invalid(ValidationError),
+    #[cfg(feature = "mjml")]
+    Mjml(crate::mjml::MjmlError),
+}
+
+impl fmt::Display for BodyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BodyError::Invalid(err) => err.fmt(f),
+            #[cfg(feature = "mjml")]
+            BodyError::Mjml(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for BodyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BodyError::Invalid(err) => Some(err),
+            #[cfg(feature = "mjml")]
+            BodyError::Mjml(err) => Some(err),
+        }
+    }
+}
+
+impl From<ValidationError> for BodyError {
+    fn from(err: ValidationError) -> Self {
+        BodyError::Invalid(err)
+    }
+}
+
+#[cfg(feature = "mjml")]
+impl From<crate::mjml::MjmlError> for BodyError {
+    fn from(err: crate::mjml::MjmlError) -> Self {
+        BodyError::Mjml(err)
+    }
+}
+
+impl From<BodyError> for ValidationError {
+    fn from(err: BodyError) -> Self {
+        match err {
+            BodyError::Invalid(err) => err,
+            #[cfg(feature = "mjml")]
+            BodyError::Mjml(err) => err.into(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -41,11 +181,119 @@ pub struct SendEmailRequest<'a> {
     pub headers: Option<HashMap<String, String>>,
 }
 
+impl SendEmailRequest<'_> {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.from.trim().is_empty() {
+            return Err(ValidationError::new("from", "a sender is required"));
+        }
+        if self.to.is_empty() {
+            return Err(ValidationError::new(
+                "to",
+                "at least one recipient is required",
+            ));
+        }
+        if self.to.len() > MAX_RECIPIENTS {
+            return Err(ValidationError::new(
+                "to",
+                format!("at most {} recipients are allowed", MAX_RECIPIENTS),
+            ));
+        }
+        if self.size() > MAX_EMAIL_SIZE {
+            return Err(ValidationError::new(
+                "size",
+                format!("emails may be at most {} bytes", MAX_EMAIL_SIZE),
+            ));
+        }
+        Ok(())
+    }
+
+    // compile errors are returned here instead of being sent to the API
+    pub fn set_body(&mut self, body: EmailBody) -> Result<(), BodyError> {
+        match body {
+            EmailBody::Html(html) => self.html = Some(html),
+            EmailBody::Text(text) => self.text = Some(text),
+            #[cfg(feature = "mjml")]
+            EmailBody::Mjml(mjml) => self.html = Some(crate::mjml::mjml_to_html(&mjml)?),
+            #[cfg(feature = "markdown")]
+            EmailBody::Markdown(markdown) => {
+                self.html = Some(markdown.to_html()?);
+                self.text = Some(markdown.to_text());
+            }
+        }
+        Ok(())
+    }
+
+    // size of the JSON body posted to the API, attachments included
+    pub fn size(&self) -> usize {
+        let mut count = ByteCount(0);
+        match serde_json::to_writer(&mut count, self) {
+            Ok(()) => count.0,
+            Err(_) => usize::MAX,
+        }
+    }
+
+    // every address in `to`, `cc` and `bcc`
+    pub fn recipients(&self) -> impl Iterator<Item = &String> {
+        self.to
+            .iter()
+            .chain(self.cc.iter().flatten())
+            .chain(self.bcc.iter().flatten())
+    }
+}
+
+// counts what is written without keeping it
+struct ByteCount(usize);
+
+impl std::io::Write for ByteCount {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+pub fn validate_batch(params: &[SendEmailRequest]) -> Result<(), ValidationError> {
+    if params.is_empty() || params.len() > MAX_BATCH_SIZE {
+        return Err(ValidationError::new(
+            "batch",
+            format!("between 1 and {} emails are allowed", MAX_BATCH_SIZE),
+        ));
+    }
+    for (index, email) in params.iter().enumerate() {
+        email.validate().map_err(|err| ValidationError {
+            field: format!("[{}].{}", index, err.field),
+            message: err.message,
+        })?;
+    }
+    Ok(())
+}
+
+// the ids of a batch are matched to its emails by position, so a response with a different
+// count is turned into an error. shared with the blocking client
+pub(crate) fn check_batch_ids(response: &mut Response<SendBatchResponse>, sent: usize) {
+    if let Ok(APIResponse::Success(batch)) = &response.result {
+        if batch.data.len() != sent {
+            response.result = Err(APIResponse::Failure(Error::BatchMismatch {
+                sent,
+                acknowledged: batch.data.len(),
+            }));
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SendEmailResponse {
     pub id: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SendBatchResponse {
+    pub data: Vec<SendEmailResponse>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Email {
     pub id: String,
@@ -62,33 +310,265 @@ pub struct Email {
     pub last_event: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListEmailsResponse {
+    pub data: Vec<Email>,
+}
+
+// rewrites outgoing emails before they are validated and sent, in the order they were added
+pub trait SendInterceptor: Send + Sync {
+    fn intercept(&self, email: &mut SendEmailRequest<'_>) -> Result<(), ValidationError>;
+}
+
 pub struct EmailService {
     pub req_client: ReqClient,
+    pub dry_run: Option<DryRun>,
+    pub interceptors: Vec<Arc<dyn SendInterceptor>>,
+}
+
+impl fmt::Debug for EmailService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmailService")
+            .field("req_client", &self.req_client)
+            .field("dry_run", &self.dry_run)
+            .field("interceptors", &self.interceptors.len())
+            .finish()
+    }
 }
 
 impl EmailService {
     pub fn new(req_client: ReqClient) -> EmailService {
-        EmailService { req_client }
+        EmailService {
+            req_client,
+            dry_run: None,
+            interceptors: vec![],
+        }
+    }
+
+    pub fn add_interceptor<I: SendInterceptor + 'static>(&mut self, interceptor: I) {
+        self.interceptors.push(Arc::new(interceptor));
+    }
+
+    // emails are only cloned when there is an interceptor that could change them
+    fn intercept<'b, 'a>(
+        &self,
+        params: &'b SendEmailRequest<'a>,
+    ) -> Result<Cow<'b, SendEmailRequest<'a>>, ValidationError> {
+        if self.interceptors.is_empty() {
+            return Ok(Cow::Borrowed(params));
+        }
+        let mut email = params.clone();
+        for interceptor in self.interceptors.iter() {
+            interceptor.intercept(&mut email)?;
+        }
+        Ok(Cow::Owned(email))
+    }
+
+    fn intercept_batch<'b, 'a>(
+        &self,
+        params: &'b [SendEmailRequest<'a>],
+    ) -> Result<Cow<'b, [SendEmailRequest<'a>]>, ValidationError> {
+        if self.interceptors.is_empty() {
+            return Ok(Cow::Borrowed(params));
+        }
+        params
+            .iter()
+            .map(|email| self.intercept(email).map(Cow::into_owned))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Cow::Owned)
     }
 
     pub async fn send<'a>(
         &self,
         params: &'a SendEmailRequest<'a>,
     ) -> Result<APIResponse<SendEmailResponse>, APIResponse<SendEmailResponse>> {
+        self.send_with_meta(params).await.result
+    }
+
+    pub async fn send_with_meta<'a>(
+        &self,
+        params: &'a SendEmailRequest<'a>,
+    ) -> Response<SendEmailResponse> {
+        let params = match self.intercept(params).and_then(|params| {
+            params.validate()?;
+            Ok(params)
+        }) {
+            Ok(params) => params,
+            Err(err) => {
+                #[cfg(feature = "otel")]
+                if let Some(metrics) = &self.req_client.metrics {
+                    metrics.record_validation(&Method::POST, "emails");
+                }
+                #[cfg(feature = "tracing")]
+                crate::trace::record_validation(
+                    &Method::POST,
+                    "emails",
+                    &err,
+                    &self.req_client.trace,
+                );
+                return Response {
+                    result: Err(APIResponse::Failure(err.into())),
+                    meta: None,
+                };
+            }
+        };
+        let params = params.as_ref();
+        if let Some(dry_run) = self.dry_run.as_ref().filter(|d| !d.passes_through(params)) {
+            return Response {
+                result: Ok(APIResponse::Success(dry_run.record(params))),
+                meta: None,
+            };
+        }
         let req = self
             .req_client
             .new_body_request(Method::POST, "emails", Some(params));
-        let result = self.req_client.exec(req).await;
-        result
+        let response = self.req_client.exec_with_meta(req).await;
+        #[cfg(feature = "otel")]
+        if let (Some(metrics), Ok(APIResponse::Success(_))) =
+            (&self.req_client.metrics, &response.result)
+        {
+            metrics.record_sent(1, params.recipients().count() as u64);
+        }
+        response
+    }
+
+    // posts the emails to `emails/batch`, counting them when metrics are enabled. a response
+    // without exactly one id per email is a failure, the ids could not be matched to the emails
+    async fn post_batch(&self, params: &[&SendEmailRequest<'_>]) -> Response<SendBatchResponse> {
+        let req = self
+            .req_client
+            .new_body_request(Method::POST, "emails/batch", Some(params));
+        let mut response = self.req_client.exec_with_meta(req).await;
+        check_batch_ids(&mut response, params.len());
+        #[cfg(feature = "otel")]
+        if let (Some(metrics), Ok(APIResponse::Success(_))) =
+            (&self.req_client.metrics, &response.result)
+        {
+            let recipients = params.iter().map(|email| email.recipients().count());
+            metrics.record_sent(params.len() as u64, recipients.sum::<usize>() as u64);
+        }
+        response
+    }
+
+    pub async fn send_batch<'a>(
+        &self,
+        params: &'a [SendEmailRequest<'a>],
+    ) -> Result<APIResponse<SendBatchResponse>, APIResponse<SendBatchResponse>> {
+        self.send_batch_with_meta(params).await.result
+    }
+
+    pub async fn send_batch_with_meta<'a>(
+        &self,
+        params: &'a [SendEmailRequest<'a>],
+    ) -> Response<SendBatchResponse> {
+        let params = match self.intercept_batch(params).and_then(|params| {
+            validate_batch(&params)?;
+            Ok(params)
+        }) {
+            Ok(params) => params,
+            Err(err) => {
+                #[cfg(feature = "otel")]
+                if let Some(metrics) = &self.req_client.metrics {
+                    metrics.record_validation(&Method::POST, "emails/batch");
+                }
+                #[cfg(feature = "tracing")]
+                crate::trace::record_validation(
+                    &Method::POST,
+                    "emails/batch",
+                    &err,
+                    &self.req_client.trace,
+                );
+                return Response {
+                    result: Err(APIResponse::Failure(err.into())),
+                    meta: None,
+                };
+            }
+        };
+        self.send_checked_batch(params.as_ref()).await
     }
 
-    pub async fn get<'a, T: Into<String>>(
+    // sends a batch that already went through the interceptors and validation
+    async fn send_checked_batch(
+        &self,
+        params: &[SendEmailRequest<'_>],
+    ) -> Response<SendBatchResponse> {
+        let dry_run = match &self.dry_run {
+            Some(dry_run) => dry_run,
+            None => return self.post_batch(&params.iter().collect::<Vec<_>>()).await,
+        };
+
+        // only the allow-listed part of the batch reaches the API, ids keep the input order
+        let passing: Vec<&SendEmailRequest> = params
+            .iter()
+            .filter(|email| dry_run.passes_through(email))
+            .collect();
+        let (mut sent, meta) = if passing.is_empty() {
+            (vec![].into_iter(), None)
+        } else {
+            let response = self.post_batch(&passing).await;
+            match response.result {
+                Ok(APIResponse::Success(batch)) => (batch.data.into_iter(), response.meta),
+                _ => return response,
+            }
+        };
+        let data = params
+            .iter()
+            .map(|email| {
+                if dry_run.passes_through(email) {
+                    // `post_batch` made sure there is one id per posted email
+                    sent.next().expect("one id per posted email")
+                } else {
+                    dry_run.record(email)
+                }
+            })
+            .collect();
+        Response {
+            result: Ok(APIResponse::Success(SendBatchResponse { data })),
+            meta,
+        }
+    }
+
+    // sends one email per recipient through `emails/batch`, MAX_BATCH_SIZE at a time. every email
+    // is expanded, intercepted and validated before the first batch goes out, a failing batch does
+    // not stop the rest
+    pub async fn send_merge<V: Serialize, I: IntoIterator<Item = Recipient<V>>>(
+        &self,
+        merge: &MailMerge<'_>,
+        recipients: I,
+    ) -> Result<MergeReport, ValidationError> {
+        let (to, emails) = merge.expand_checked(recipients, |email| {
+            let email = self.intercept(email)?;
+            email.validate()?;
+            Ok(email.into_owned())
+        })?;
+        let mut report = MergeReport::default();
+        for (to, chunk) in to.chunks(MAX_BATCH_SIZE).zip(emails.chunks(MAX_BATCH_SIZE)) {
+            report.record_batch(to, self.send_checked_batch(chunk).await.result);
+        }
+        Ok(report)
+    }
+
+    pub async fn get<T: Into<String>>(
         &self,
         email_id: T,
     ) -> Result<APIResponse<Email>, APIResponse<Email>> {
+        self.get_with_meta(email_id).await.result
+    }
+
+    pub async fn get_with_meta<T: Into<String>>(&self, email_id: T) -> Response<Email> {
         let path = format!("emails/{}", email_id.into());
-        let req = self.req_client.new_request(Method::GET, &path[..]);
-        let result = self.req_client.exec::<Email>(req).await;
-        result
+        let req = self.req_client.new_request(Method::GET, &path);
+        self.req_client.exec_with_meta(req).await
+    }
+
+    pub async fn list(
+        &self,
+    ) -> Result<APIResponse<ListEmailsResponse>, APIResponse<ListEmailsResponse>> {
+        self.list_with_meta().await.result
+    }
+
+    pub async fn list_with_meta(&self) -> Response<ListEmailsResponse> {
+        let req = self.req_client.new_request(Method::GET, "emails");
+        self.req_client.exec_with_meta(req).await
     }
 }
@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+
+use mail_builder::headers::address::Address;
+use mail_builder::headers::content_type::ContentType;
+use mail_builder::headers::text::Text;
+use mail_builder::mime::MimePart;
+use mail_builder::MessageBuilder;
+use mail_parser::{Message, MessageParser, MimeHeaders};
+
+use crate::emails::{Attachment, SendEmailRequest};
+use crate::reqlib::ValidationError;
+
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+// headers that are read into fields of the request or describe the original delivery,
+// anything else is carried over as a custom header
+const SKIPPED_HEADERS: [&str; 17] = [
+    "authentication-results",
+    "bcc",
+    "cc",
+    "date",
+    "delivered-to",
+    "dkim-signature",
+    "from",
+    "message-id",
+    "mime-version",
+    "received",
+    "reply-to",
+    "return-path",
+    "sender",
+    "subject",
+    "to",
+    "x-mailer",
+    "x-received",
+];
+const SKIPPED_PREFIXES: [&str; 3] = ["arc-", "content-", "resent-"];
+
+// renders the email as an RFC 5322 message that mail clients open as a `.eml` file. html and
+// text become multipart/alternative, attachments with a content id are kept next to the html in
+// multipart/related and the others go into multipart/mixed. Message-ID and Date are generated
+// unless `headers` sets them. bcc recipients are left out, like in the copy other recipients get
+pub fn to_eml(email: &SendEmailRequest<'_>) -> Result<Vec<u8>, ValidationError> {
+    write_eml(email, false)
+}
+
+// like `to_eml` but with a `Bcc` header, e.g. for the sender's own archive
+pub fn to_eml_with_bcc(email: &SendEmailRequest<'_>) -> Result<Vec<u8>, ValidationError> {
+    write_eml(email, true)
+}
+
+fn write_eml(email: &SendEmailRequest<'_>, with_bcc: bool) -> Result<Vec<u8>, ValidationError> {
+    let mut message = MessageBuilder::new()
+        .from(address(&email.from))
+        .to(address_list(&email.to))
+        .subject(email.subject.as_str());
+    if let Some(cc) = &email.cc {
+        message = message.cc(address_list(cc));
+    }
+    if let (true, Some(bcc)) = (with_bcc, &email.bcc) {
+        message = message.bcc(address_list(bcc));
+    }
+    if let Some(reply_to) = &email.reply_to {
+        message = message.reply_to(address(reply_to));
+    }
+    // the builder only skips generating Message-ID and Date for these exact spellings
+    let mut headers: Vec<_> = email
+        .headers
+        .iter()
+        .flatten()
+        .map(|(name, value)| match name.as_str() {
+            name if name.eq_ignore_ascii_case("message-id") => ("Message-ID", value),
+            name if name.eq_ignore_ascii_case("date") => ("Date", value),
+            name => (name, value),
+        })
+        .collect();
+    headers.sort();
+    for (name, value) in headers {
+        message = message.header(name, Text::new(value.as_str()));
+    }
+
+    let attachments = email.attachments.as_deref().unwrap_or_default();
+    let mut inline = vec![];
+    let mut attached = vec![];
+    for attachment in attachments {
+        if attachment.path().is_some() && attachment.content().is_empty() {
+            return Err(ValidationError::new(
+                "attachments",
+                format!(
+                    "`{}` is only referenced by path and has no content to include",
+                    attachment.filename()
+                ),
+            ));
+        }
+        match (attachment.content_id(), &email.html) {
+            (Some(content_id), Some(_)) => inline.push(
+                attachment_part(attachment)
+                    .inline()
+                    .cid(content_id.trim_matches(['<', '>'])),
+            ),
+            _ => attached.push(attachment_part(attachment).attachment(attachment.filename())),
+        }
+    }
+
+    let html = email.html.as_deref().map(|html| {
+        let part = MimePart::new("text/html", html);
+        match inline.is_empty() {
+            true => part,
+            false => MimePart::new(
+                ContentType::new("multipart/related").attribute("type", "text/html"),
+                [vec![part], inline].concat(),
+            ),
+        }
+    });
+    let text = email
+        .text
+        .as_deref()
+        .map(|text| MimePart::new("text/plain", text));
+    let body = match (text, html) {
+        (Some(text), Some(html)) => Some(MimePart::new("multipart/alternative", vec![text, html])),
+        (text, html) => text.or(html),
+    };
+    let body = match (body, attached.is_empty()) {
+        (Some(body), true) => body,
+        (body, _) => MimePart::new(
+            "multipart/mixed",
+            body.into_iter().chain(attached).collect::<Vec<_>>(),
+        ),
+    };
+    message.body(body).write_to_vec().map_err(|err| {
+        ValidationError::new("email", format!("could not write the message: {}", err))
+    })
+}
+
+// a message read by `from_eml`, it owns the attachments that its request borrows
+#[derive(Debug, Clone)]
+pub struct ParsedEmail {
+    // everything but the attachments
+    pub email: SendEmailRequest<'static>,
+    pub attachments: Vec<Attachment>,
+}
+
+impl ParsedEmail {
+    pub fn request(&self) -> SendEmailRequest<'_> {
+        SendEmailRequest {
+            attachments: match self.attachments.is_empty() {
+                true => None,
+                false => Some(self.attachments.iter().collect()),
+            },
+            ..self.email.clone()
+        }
+    }
+}
+
+// reads an RFC 5322 message so it can be relayed through Resend. the first html and text parts
+// become the body, every other part an attachment and unknown headers are kept in `headers`
+pub fn from_eml(eml: &[u8]) -> Result<ParsedEmail, ValidationError> {
+    let message = MessageParser::new()
+        .with_minimal_headers()
+        .default_header_text()
+        .parse(eml)
+        .filter(|message| !message.headers().is_empty())
+        .ok_or_else(|| ValidationError::new("eml", "not an RFC 5322 message"))?;
+    let from = addresses(message.from())
+        .into_iter()
+        .next()
+        .ok_or_else(|| ValidationError::new("from", "the message has no sender"))?;
+    // a custom header can only have one value, so a repeated one would lose all but the last
+    let mut headers: HashMap<String, String> = HashMap::new();
+    for header in message.headers() {
+        let name = header.name().to_ascii_lowercase();
+        let value = match header.value().as_text() {
+            Some(value) => value.trim().to_string(),
+            None => continue,
+        };
+        if SKIPPED_HEADERS.contains(&name.as_str())
+            || SKIPPED_PREFIXES
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+        {
+            continue;
+        }
+        if headers
+            .keys()
+            .any(|known| known.eq_ignore_ascii_case(&name))
+        {
+            return Err(ValidationError::new(
+                "headers",
+                format!("`{}` appears more than once", header.name()),
+            ));
+        }
+        headers.insert(header.name().to_string(), value);
+    }
+    let email = SendEmailRequest {
+        subject: message.subject().unwrap_or_default().to_string(),
+        from,
+        to: addresses(message.to()),
+        cc: Some(addresses(message.cc())).filter(|cc| !cc.is_empty()),
+        bcc: Some(addresses(message.bcc())).filter(|bcc| !bcc.is_empty()),
+        reply_to: addresses(message.reply_to()).into_iter().next(),
+        html: message
+            .html_bodies()
+            .find(|part| part.is_text_html())
+            .and_then(|part| part.text_contents())
+            .map(str::to_string),
+        text: message
+            .text_bodies()
+            .find(|part| part.is_text() && !part.is_text_html())
+            .and_then(|part| part.text_contents())
+            .map(str::to_string),
+        tags: None,
+        attachments: None,
+        headers: Some(headers).filter(|headers| !headers.is_empty()),
+    };
+    Ok(ParsedEmail {
+        email,
+        attachments: attachments(&message),
+    })
+}
+
+fn attachments(message: &Message<'_>) -> Vec<Attachment> {
+    message
+        .attachments()
+        .enumerate()
+        .map(|(index, part)| {
+            let content_type =
+                part.content_type()
+                    .map(|content_type| match content_type.subtype() {
+                        Some(subtype) => format!("{}/{}", content_type.ctype(), subtype),
+                        None => content_type.ctype().to_string(),
+                    });
+            let filename = part
+                .attachment_name()
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("attachment-{}", index + 1));
+            let mut attachment = Attachment::new(filename, part.contents());
+            if let Some(content_type) = content_type {
+                attachment = attachment.with_content_type(content_type.to_ascii_lowercase());
+            }
+            if let Some(content_id) = part.content_id() {
+                attachment = attachment.with_content_id(content_id.trim_matches(['<', '>']));
+            }
+            attachment
+        })
+        .collect()
+}
+
+// `Name <hi@domain.com>` when the address has a display name, members of groups are included
+fn addresses(address: Option<&mail_parser::Address<'_>>) -> Vec<String> {
+    address
+        .into_iter()
+        .flat_map(|address| address.iter())
+        .filter_map(|addr| {
+            let email = addr.address()?;
+            Some(match addr.name().filter(|name| !name.trim().is_empty()) {
+                Some(name) if name.contains(['"', ',', '<', '>', '@', ';', ':']) => {
+                    format!("\"{}\" <{}>", name.replace(['\\', '"'], ""), email)
+                }
+                Some(name) => format!("{} <{}>", name, email),
+                None => email.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn attachment_part(attachment: &Attachment) -> MimePart<'_> {
+    let content_type = attachment
+        .content_type()
+        .unwrap_or_else(|| guess_content_type(attachment.filename()));
+    MimePart::new(content_type, attachment.content())
+}
+
+fn guess_content_type(filename: &str) -> &'static str {
+    let extension = filename.rsplit_once('.').map(|(_, extension)| extension);
+    match extension.map(str::to_ascii_lowercase).as_deref() {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("webp") => "image/webp",
+        Some("pdf") => "application/pdf",
+        Some("zip") => "application/zip",
+        Some("json") => "application/json",
+        Some("txt") => "text/plain",
+        Some("csv") => "text/csv",
+        Some("htm" | "html") => "text/html",
+        Some("ics") => "text/calendar",
+        _ => DEFAULT_CONTENT_TYPE,
+    }
+}
+
+fn address(value: &str) -> Address<'_> {
+    let (name, email) = split_address(value);
+    Address::new_address(name, email)
+}
+
+// accepts both `hi@domain.com` and `Name <hi@domain.com>`
+pub(crate) fn split_address(value: &str) -> (Option<&str>, &str) {
+    let value = value.trim();
+    match value
+        .strip_suffix('>')
+        .and_then(|rest| rest.rsplit_once('<'))
+    {
+        Some((name, email)) => {
+            let name = name.trim().trim_matches('"').trim();
+            ((!name.is_empty()).then_some(name), email.trim())
+        }
+        None => (None, value),
+    }
+}
+
+fn address_list(values: &[String]) -> Address<'_> {
+    Address::new_list(values.iter().map(|value| address(value)).collect())
+}
@@ -0,0 +1,248 @@
+use serde::Serialize;
+
+use crate::domains::{DnsRecord, DnsRecordType, DomainDetails, Region};
+
+// TXT character-strings are limited to 255 bytes each
+const TXT_CHUNK_SIZE: usize = 255;
+// used wherever a provider needs a concrete TTL and Resend returned "Auto"
+const DEFAULT_TTL: u32 = 3600;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RecordManifest {
+    pub domain: String,
+    pub region: Region,
+    pub records: Vec<ManifestRecord>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ManifestRecord {
+    pub record: String,
+    pub r#type: String,
+    pub name: String,
+    pub fully_qualified_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u16>,
+}
+
+pub fn manifest(domain: &DomainDetails) -> RecordManifest {
+    RecordManifest {
+        domain: domain.name.clone(),
+        region: domain.region.clone(),
+        records: domain
+            .records
+            .iter()
+            .map(|record| ManifestRecord {
+                record: record.record.as_str().to_string(),
+                r#type: record.r#type.as_str().to_string(),
+                name: record.name.clone(),
+                fully_qualified_name: record.fully_qualified_name(&domain.name),
+                ttl: ttl(record),
+                value: unquote_txt(&record.value),
+                priority: record.priority,
+            })
+            .collect(),
+    }
+}
+
+pub fn to_json(domain: &DomainDetails) -> String {
+    serde_json::to_string_pretty(&manifest(domain)).unwrap()
+}
+
+#[cfg(feature = "yaml")]
+pub fn to_yaml(domain: &DomainDetails) -> String {
+    serde_yaml::to_string(&manifest(domain)).unwrap()
+}
+
+pub fn to_bind_zone(domain: &DomainDetails) -> String {
+    let mut zone = format!(
+        "; Resend records for {}\n$ORIGIN {}.\n",
+        domain.name,
+        domain.name.trim_end_matches('.')
+    );
+    for record in domain.records.iter() {
+        let name = format!("{}.", record.fully_qualified_name(&domain.name));
+        let ttl = ttl(record).unwrap_or(DEFAULT_TTL);
+        let data = match record.r#type {
+            DnsRecordType::Mx => format!(
+                "{} {}",
+                record.priority.unwrap_or(10),
+                absolute_host(&record.value)
+            ),
+            DnsRecordType::Cname => absolute_host(&record.value),
+            DnsRecordType::Txt => txt_chunks(&unquote_txt(&record.value))
+                .iter()
+                .map(|chunk| format!("\"{}\"", escape_zone(chunk)))
+                .collect::<Vec<String>>()
+                .join(" "),
+        };
+        zone.push_str(&format!(
+            "{}\t{}\tIN\t{}\t{}\n",
+            name,
+            ttl,
+            record.r#type.as_str(),
+            data
+        ));
+    }
+    zone
+}
+
+// `zone_id` is either the zone id itself or a reference like `var.zone_id`
+pub fn to_terraform_cloudflare(domain: &DomainDetails, zone_id: &str) -> String {
+    let mut blocks: Vec<String> = vec![];
+    for (index, record) in domain.records.iter().enumerate() {
+        let mut block = format!(
+            "resource \"cloudflare_dns_record\" \"{}\" {{\n",
+            resource_label(domain, record, index)
+        );
+        block.push_str(&format!("  zone_id  = {}\n", zone_expression(zone_id)));
+        block.push_str(&format!(
+            "  name     = \"{}\"\n",
+            escape_hcl(&record.fully_qualified_name(&domain.name))
+        ));
+        block.push_str(&format!("  type     = \"{}\"\n", record.r#type.as_str()));
+        let content = match record.r#type {
+            DnsRecordType::Txt => unquote_txt(&record.value),
+            _ => record.value.trim_end_matches('.').to_string(),
+        };
+        block.push_str(&format!("  content  = \"{}\"\n", escape_hcl(&content)));
+        // a TTL of 1 means "automatic" for Cloudflare
+        block.push_str(&format!("  ttl      = {}\n", ttl(record).unwrap_or(1)));
+        if let (DnsRecordType::Mx, Some(priority)) = (&record.r#type, record.priority) {
+            block.push_str(&format!("  priority = {}\n", priority));
+        }
+        block.push_str("}\n");
+        blocks.push(block);
+    }
+    blocks.join("\n")
+}
+
+// `zone_id` is either the zone id itself or a reference like `aws_route53_zone.main.zone_id`
+pub fn to_terraform_route53(domain: &DomainDetails, zone_id: &str) -> String {
+    let mut blocks: Vec<String> = vec![];
+    for (index, record) in domain.records.iter().enumerate() {
+        let mut block = format!(
+            "resource \"aws_route53_record\" \"{}\" {{\n",
+            resource_label(domain, record, index)
+        );
+        block.push_str(&format!("  zone_id = {}\n", zone_expression(zone_id)));
+        block.push_str(&format!(
+            "  name    = \"{}\"\n",
+            escape_hcl(&record.fully_qualified_name(&domain.name))
+        ));
+        block.push_str(&format!("  type    = \"{}\"\n", record.r#type.as_str()));
+        block.push_str(&format!(
+            "  ttl     = {}\n",
+            ttl(record).unwrap_or(DEFAULT_TTL)
+        ));
+        let value = match record.r#type {
+            DnsRecordType::Mx => format!(
+                "{} {}",
+                record.priority.unwrap_or(10),
+                record.value.trim_end_matches('.')
+            ),
+            DnsRecordType::Cname => record.value.trim_end_matches('.').to_string(),
+            // Route53 splits long values into character-strings wherever `""` appears
+            DnsRecordType::Txt => txt_chunks(&unquote_txt(&record.value))
+                .iter()
+                .map(|chunk| escape_hcl(&escape_zone(chunk)))
+                .collect::<Vec<String>>()
+                .join("\\\"\\\""),
+        };
+        let value = match record.r#type {
+            DnsRecordType::Txt => value,
+            _ => escape_hcl(&value),
+        };
+        block.push_str(&format!("  records = [\"{}\"]\n", value));
+        block.push_str("}\n");
+        blocks.push(block);
+    }
+    blocks.join("\n")
+}
+
+fn ttl(record: &DnsRecord) -> Option<u32> {
+    record.ttl.trim().parse::<u32>().ok()
+}
+
+fn absolute_host(value: &str) -> String {
+    format!("{}.", value.trim().trim_end_matches('.'))
+}
+
+fn unquote_txt(value: &str) -> String {
+    let value = value.trim();
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        value[1..value.len() - 1].split("\" \"").collect()
+    } else {
+        value.to_string()
+    }
+}
+
+fn txt_chunks(value: &str) -> Vec<&str> {
+    let mut chunks = vec![];
+    let mut rest = value;
+    while rest.len() > TXT_CHUNK_SIZE {
+        let mut split = TXT_CHUNK_SIZE;
+        while !rest.is_char_boundary(split) {
+            split -= 1;
+        }
+        let (chunk, tail) = rest.split_at(split);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    chunks.push(rest);
+    chunks
+}
+
+fn escape_zone(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_hcl(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace("${", "$${")
+        .replace("%{", "%%{")
+}
+
+// references such as `var.zone_id` are emitted as-is, anything else becomes a quoted string
+fn zone_expression(zone_id: &str) -> String {
+    let identifier = |part: &str| {
+        part.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    };
+    let zone_id = zone_id.trim();
+    match zone_id.contains('.') && zone_id.split('.').all(identifier) {
+        true => zone_id.to_string(),
+        false => format!("\"{}\"", escape_hcl(zone_id)),
+    }
+}
+
+// labels may only hold letters, digits, `_` and `-` and must not start with a digit
+fn resource_label(domain: &DomainDetails, record: &DnsRecord, index: usize) -> String {
+    let label = format!(
+        "{}_{}_{}_{}",
+        domain.name,
+        record.record.as_str(),
+        record.r#type.as_str(),
+        index
+    );
+    let label: String = label
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    match label.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        true => label,
+        false => format!("_{}", label),
+    }
+}
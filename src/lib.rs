@@ -1,11 +1,51 @@
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod cassette;
+#[cfg(feature = "css-inline")]
+pub mod css;
+pub mod diagnostics;
 pub mod domains;
+pub mod dry_run;
 pub mod emails;
+#[cfg(feature = "eml")]
+pub mod eml;
+pub mod export;
+#[cfg(feature = "markdown")]
+pub mod markdown;
+pub mod merge;
+pub mod middleware;
+#[cfg(feature = "mjml")]
+pub mod mjml;
+#[cfg(feature = "otel")]
+pub mod otel;
+pub mod plain_text;
+pub mod redirect;
 pub mod reqlib;
+pub mod secret;
+#[cfg(feature = "smtp")]
+pub mod smtp;
+pub mod templates;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "tracing")]
+pub mod trace;
+pub mod transport;
+
+use std::sync::Arc;
+
+use http::Method;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 
 use domains::DomainService;
+use dry_run::DryRun;
 use emails::EmailService;
-use reqlib::ReqClient;
+use middleware::Middleware;
+use reqlib::{APIResponse, ReqClient, Response};
+use secret::ApiKey;
+use transport::Transport;
 
+#[derive(Debug)]
 pub struct Client {
     pub raw_client: ReqClient,
     pub email_service: EmailService,
@@ -13,14 +53,95 @@ pub struct Client {
 }
 
 impl Client {
-    pub fn new<T: Into<String> + Clone>(api_key: T) -> Client {
-        let raw_client = ReqClient::new(api_key.clone().into());
-        let email_service = EmailService::new(ReqClient::new(api_key.clone().into()));
-        let domain_service = DomainService::new(ReqClient::new(api_key.clone().into()));
+    pub fn new<T: Into<ApiKey>>(api_key: T) -> Client {
+        let api_key = api_key.into();
+        let raw_client = ReqClient::new(api_key.clone());
+        let email_service = EmailService::new(ReqClient::new(api_key.clone()));
+        let domain_service = DomainService::new(ReqClient::new(api_key));
+        Client {
+            raw_client,
+            email_service,
+            domain_service,
+        }
+    }
+
+    // reads the key from `RESEND_API_KEY`
+    pub fn from_env() -> Result<Client, std::env::VarError> {
+        ApiKey::from_env().map(Client::new)
+    }
+
+    // reads the key from a file, e.g. a mounted Docker or Kubernetes secret
+    pub fn from_key_file<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Client> {
+        ApiKey::from_file(path).map(Client::new)
+    }
+
+    // all services share the given transport, e.g. an in-memory fake for tests
+    pub fn with_transport<T: Into<ApiKey>>(api_key: T, transport: Arc<dyn Transport>) -> Client {
+        let api_key = api_key.into();
+        let raw_client = ReqClient::with_transport(transport.clone(), api_key.clone());
+        let email_service = EmailService::new(ReqClient::with_transport(
+            transport.clone(),
+            api_key.clone(),
+        ));
+        let domain_service = DomainService::new(ReqClient::with_transport(transport, api_key));
         Client {
             raw_client,
             email_service,
             domain_service,
         }
     }
+
+    // e.g. `client.request::<(), Value>(Method::GET, "api-keys", &[], None)` for endpoints
+    // this crate does not wrap yet
+    pub async fn request<Req: Serialize + ?Sized, Resp: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&Req>,
+    ) -> Result<APIResponse<Resp>, APIResponse<Resp>> {
+        self.raw_client.request(method, path, query, body).await
+    }
+
+    pub async fn request_with_meta<Req: Serialize + ?Sized, Resp: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&Req>,
+    ) -> Response<Resp> {
+        self.raw_client
+            .request_with_meta(method, path, query, body)
+            .await
+    }
+
+    // sends are validated and recorded but only reach the API for allow-listed recipients
+    pub fn enable_dry_run(&mut self, dry_run: DryRun) {
+        self.email_service.dry_run = Some(dry_run);
+    }
+
+    // adds the middleware to the raw client and every service
+    pub fn add_middleware<M: Middleware + 'static>(&mut self, middleware: M) {
+        let middleware: Arc<dyn Middleware> = Arc::new(middleware);
+        for req_client in [
+            &mut self.raw_client,
+            &mut self.email_service.req_client,
+            &mut self.domain_service.req_client,
+        ] {
+            req_client.middleware.push(middleware.clone());
+        }
+    }
+
+    // records request and send metrics for the raw client and every service
+    #[cfg(feature = "otel")]
+    pub fn enable_metrics(&mut self, meter: &opentelemetry::metrics::Meter) {
+        let metrics = Arc::new(otel::Metrics::new(meter));
+        for req_client in [
+            &mut self.raw_client,
+            &mut self.email_service.req_client,
+            &mut self.domain_service.req_client,
+        ] {
+            req_client.metrics = Some(metrics.clone());
+        }
+    }
 }
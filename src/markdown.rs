@@ -0,0 +1,57 @@
+use pulldown_cmark::{html, Options, Parser};
+
+use crate::plain_text::html_to_text;
+use crate::reqlib::ValidationError;
+
+// marks where a layout takes the rendered markdown
+pub const LAYOUT_CONTENT: &str = "{{content}}";
+
+const DEFAULT_LAYOUT: &str = r#"<!DOCTYPE html><html><head><meta charset="utf-8"><meta name="viewport" content="width=device-width, initial-scale=1"></head><body><div style="max-width: 600px; margin: 0 auto; font-family: sans-serif; line-height: 1.5">{{content}}</div></body></html>"#;
+
+// CommonMark with tables, rendered into both `html` and `text` of an email
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Markdown {
+    pub source: String,
+    // html wrapped around the rendered markdown, the default layout is used when `None`
+    pub layout: Option<String>,
+}
+
+impl Markdown {
+    pub fn new<S: Into<String>>(source: S) -> Self {
+        Markdown {
+            source: source.into(),
+            layout: None,
+        }
+    }
+
+    pub fn with_layout<L: Into<String>>(mut self, layout: L) -> Self {
+        self.layout = Some(layout.into());
+        self
+    }
+
+    // the rendered markdown without a layout
+    pub fn content_html(&self) -> String {
+        let mut out = String::with_capacity(self.source.len() * 3 / 2);
+        html::push_html(
+            &mut out,
+            Parser::new_ext(&self.source, Options::ENABLE_TABLES),
+        );
+        out
+    }
+
+    pub fn to_html(&self) -> Result<String, ValidationError> {
+        let layout = self.layout.as_deref().unwrap_or(DEFAULT_LAYOUT);
+        if !layout.contains(LAYOUT_CONTENT) {
+            return Err(ValidationError::new(
+                "html",
+                format!("the markdown layout has no {} placeholder", LAYOUT_CONTENT),
+            ));
+        }
+        Ok(layout.replacen(LAYOUT_CONTENT, &self.content_html(), 1))
+    }
+
+    // only the markdown itself, the layout is left out of the text
+    pub fn to_text(&self) -> String {
+        html_to_text(&self.content_html())
+    }
+}
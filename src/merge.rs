@@ -0,0 +1,283 @@
+use std::fmt;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::emails::{SendBatchResponse, SendEmailRequest};
+use crate::reqlib::{APIResponse, Error, ValidationError};
+
+// one recipient of a mail merge with the values for its placeholders, e.g. a CSV row
+// deserialized into a `HashMap<String, String>` or any serializable struct
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recipient<V> {
+    pub to: String,
+    pub variables: V,
+}
+
+impl<V> Recipient<V> {
+    pub fn new<T: Into<String>>(to: T, variables: V) -> Self {
+        Recipient {
+            to: to.into(),
+            variables,
+        }
+    }
+}
+
+// a template email whose subject, html and text contain `{{placeholders}}`. nested values
+// are reached with dots, e.g. `{{ user.name }}`, and values are html escaped in the html part
+#[derive(Debug, Clone)]
+pub struct MailMerge<'a> {
+    pub template: SendEmailRequest<'a>,
+}
+
+impl<'a> MailMerge<'a> {
+    pub fn new(template: SendEmailRequest<'a>) -> Self {
+        MailMerge { template }
+    }
+
+    // every placeholder name used by the template, in order of first use
+    pub fn placeholders(&self) -> Result<Vec<String>, ValidationError> {
+        let mut names: Vec<String> = vec![];
+        for (field, source) in self.parts() {
+            for segment in parse(field, source)? {
+                if let Segment::Placeholder(name) = segment {
+                    if !names.iter().any(|known| known == name) {
+                        names.push(name.to_string());
+                    }
+                }
+            }
+        }
+        Ok(names)
+    }
+
+    // one email per recipient, sent to that recipient only, so the template's cc and bcc are
+    // dropped. fails on the first placeholder a recipient has no value for, errors are reported
+    // as `[index].field` like batches
+    pub fn expand<V: Serialize, I: IntoIterator<Item = Recipient<V>>>(
+        &self,
+        recipients: I,
+    ) -> Result<Vec<SendEmailRequest<'a>>, ValidationError> {
+        let parts = self
+            .parts()
+            .map(|(field, source)| parse(field, source).map(|segments| (field, segments)))
+            .collect::<Result<Vec<_>, _>>()?;
+        recipients
+            .into_iter()
+            .enumerate()
+            .map(|(index, recipient)| {
+                let variables = serde_json::to_value(&recipient.variables).map_err(|err| {
+                    ValidationError::new(format!("[{}].variables", index), err.to_string())
+                })?;
+                let mut email = self.template.clone();
+                email.to = vec![recipient.to];
+                email.cc = None;
+                email.bcc = None;
+                for (field, segments) in parts.iter() {
+                    let merged = merge(field, segments, &variables).map_err(|err| {
+                        ValidationError::new(format!("[{}].{}", index, err.field), err.message)
+                    })?;
+                    match *field {
+                        "subject" => email.subject = merged,
+                        "html" => email.html = Some(merged),
+                        _ => email.text = Some(merged),
+                    }
+                }
+                Ok(email)
+            })
+            .collect()
+    }
+
+    // expands the emails and runs `check` on each of them, e.g. interceptors and validation, keeping
+    // the recipient every email was expanded for so the report does not depend on what `check`
+    // does to the recipients
+    pub(crate) fn expand_checked<V, I, F>(
+        &self,
+        recipients: I,
+        check: F,
+    ) -> Result<(Vec<String>, Vec<SendEmailRequest<'a>>), ValidationError>
+    where
+        V: Serialize,
+        I: IntoIterator<Item = Recipient<V>>,
+        F: Fn(&SendEmailRequest<'a>) -> Result<SendEmailRequest<'a>, ValidationError>,
+    {
+        let emails = self.expand(recipients)?;
+        let to = emails.iter().map(|email| email.to[0].clone()).collect();
+        let emails = emails
+            .iter()
+            .enumerate()
+            .map(|(index, email)| {
+                check(email).map_err(|err| ValidationError {
+                    field: format!("[{}].{}", index, err.field),
+                    message: err.message,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((to, emails))
+    }
+
+    fn parts(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        [
+            ("subject", Some(self.template.subject.as_str())),
+            ("html", self.template.html.as_deref()),
+            ("text", self.template.text.as_deref()),
+        ]
+        .into_iter()
+        .filter_map(|(field, source)| source.map(|source| (field, source)))
+    }
+}
+
+// why a merged email was not sent
+#[derive(Debug, Clone)]
+pub enum MergeError {
+    // the batch it was part of failed, the response is shared by every email in that batch
+    Batch(Arc<APIResponse<SendBatchResponse>>),
+    // the batch was accepted without an id for this email
+    MissingId,
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::Batch(response) => write!(f, "the batch failed: {:?}", response),
+            MergeError::MissingId => write!(f, "the batch response has no id for this email"),
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+#[derive(Debug)]
+pub struct MergeResult {
+    pub to: String,
+    // the id of the sent email
+    pub result: Result<String, MergeError>,
+}
+
+// one result per recipient, in the order they were given
+#[derive(Debug, Default)]
+pub struct MergeReport {
+    pub results: Vec<MergeResult>,
+}
+
+impl MergeReport {
+    pub fn is_ok(&self) -> bool {
+        self.results.iter().all(|result| result.result.is_ok())
+    }
+
+    pub fn sent(&self) -> impl Iterator<Item = &MergeResult> {
+        self.results.iter().filter(|result| result.result.is_ok())
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &MergeResult> {
+        self.results.iter().filter(|result| result.result.is_err())
+    }
+
+    // adds the results of one batch, `to` holds the recipient of every email in it
+    pub(crate) fn record_batch(
+        &mut self,
+        to: &[String],
+        result: Result<APIResponse<SendBatchResponse>, APIResponse<SendBatchResponse>>,
+    ) {
+        match result {
+            Ok(APIResponse::Success(batch)) => {
+                let mut ids = batch.data.into_iter();
+                for to in to {
+                    let result = ids.next().map(|sent| sent.id).ok_or(MergeError::MissingId);
+                    self.results.push(MergeResult {
+                        to: to.clone(),
+                        result,
+                    });
+                }
+            }
+            Ok(response) | Err(response) => {
+                // the emails before the one that failed went out and keep their ids
+                let mut delivered = match &response {
+                    APIResponse::Failure(Error::PartialBatch { delivered, .. }) => {
+                        delivered.clone().into_iter()
+                    }
+                    _ => vec![].into_iter(),
+                };
+                let response = Arc::new(response);
+                self.results.extend(to.iter().map(|to| {
+                    MergeResult {
+                        to: to.clone(),
+                        result: delivered
+                            .next()
+                            .ok_or_else(|| MergeError::Batch(response.clone())),
+                    }
+                }));
+            }
+        }
+    }
+}
+
+enum Segment<'s> {
+    Text(&'s str),
+    Placeholder(&'s str),
+}
+
+fn parse<'s>(field: &str, source: &'s str) -> Result<Vec<Segment<'s>>, ValidationError> {
+    let mut segments = vec![];
+    let mut rest = source;
+    while let Some(start) = rest.find("{{") {
+        segments.push(Segment::Text(&rest[..start]));
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| ValidationError::new(field, "unclosed `{{` placeholder"))?;
+        let name = after[..end].trim();
+        let valid = |c: char| c.is_alphanumeric() || matches!(c, '_' | '-' | '.');
+        if name.is_empty() || !name.chars().all(valid) {
+            return Err(ValidationError::new(
+                field,
+                format!("invalid placeholder `{{{{{}}}}}`", &after[..end]),
+            ));
+        }
+        segments.push(Segment::Placeholder(name));
+        rest = &after[end + 2..];
+    }
+    segments.push(Segment::Text(rest));
+    Ok(segments)
+}
+
+fn merge(field: &str, segments: &[Segment], variables: &Value) -> Result<String, ValidationError> {
+    let mut out = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => out.push_str(text),
+            Segment::Placeholder(name) => {
+                let value = name
+                    .split('.')
+                    .try_fold(variables, |value, key| value.get(key))
+                    .and_then(|value| match value {
+                        Value::String(value) => Some(value.clone()),
+                        Value::Number(value) => Some(value.to_string()),
+                        Value::Bool(value) => Some(value.to_string()),
+                        _ => None,
+                    })
+                    .ok_or_else(|| {
+                        ValidationError::new(field, format!("`{{{{{}}}}}` has no value", name))
+                    })?;
+                match field {
+                    "html" => escape_html(&mut out, &value),
+                    _ => out.push_str(&value),
+                }
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn escape_html(out: &mut String, value: &str) {
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use http::header::{HeaderMap, HeaderName, HeaderValue};
+
+use crate::transport::{Transport, TransportFuture, TransportRequest};
+
+// wraps every request sent by a `ReqClient`. code before `next.run` sees the outgoing request,
+// code after it sees the response, and returning without calling `next` short-circuits.
+pub trait Middleware: Send + Sync {
+    fn handle<'a>(&'a self, request: TransportRequest, next: Next<'a>) -> TransportFuture<'a>;
+}
+
+// `Next` is `Copy`, so a middleware may run the rest of the chain more than once, e.g. to retry
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    middleware: &'a [Arc<dyn Middleware>],
+    transport: &'a dyn Transport,
+    // number of times the transport was reached for this request
+    transport_calls: &'a AtomicU32,
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(
+        middleware: &'a [Arc<dyn Middleware>],
+        transport: &'a dyn Transport,
+        transport_calls: &'a AtomicU32,
+    ) -> Self {
+        Next {
+            middleware,
+            transport,
+            transport_calls,
+        }
+    }
+
+    pub fn run(self, request: TransportRequest) -> TransportFuture<'a> {
+        match self.middleware.split_first() {
+            Some((current, rest)) => current.handle(
+                request,
+                Next {
+                    middleware: rest,
+                    ..self
+                },
+            ),
+            None => {
+                let call = self.transport_calls.fetch_add(1, Ordering::SeqCst) + 1;
+                send(self.transport, request, call)
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+fn send(transport: &dyn Transport, request: TransportRequest, _call: u32) -> TransportFuture<'_> {
+    transport.send(request)
+}
+
+#[cfg(feature = "tracing")]
+fn send(transport: &dyn Transport, request: TransportRequest, call: u32) -> TransportFuture<'_> {
+    use tracing::Instrument;
+
+    let span = tracing::debug_span!("resend.transport", call);
+    Box::pin(transport.send(request).instrument(span))
+}
+
+impl TransportRequest {
+    // the API path without the base url, e.g. `/emails`
+    pub fn path(&self) -> &str {
+        self.url.path()
+    }
+
+    pub fn json_body(&self) -> Option<serde_json::Value> {
+        self.body
+            .as_deref()
+            .and_then(|body| serde_json::from_slice(body).ok())
+    }
+
+    pub fn set_json_body(&mut self, body: &serde_json::Value) {
+        self.body = Some(serde_json::to_vec(body).unwrap());
+    }
+}
+
+// logs every request and its outcome through the `log` crate
+#[derive(Debug, Clone, Default)]
+pub struct Logging {
+    // request and response bodies contain addresses and content, keep this off in production
+    pub log_bodies: bool,
+}
+
+impl Logging {
+    pub fn new() -> Self {
+        Logging::default()
+    }
+
+    pub fn with_bodies(mut self) -> Self {
+        self.log_bodies = true;
+        self
+    }
+}
+
+impl Middleware for Logging {
+    fn handle<'a>(&'a self, request: TransportRequest, next: Next<'a>) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let method = request.method.clone();
+            let path = request.path().to_string();
+            if self.log_bodies {
+                if let Some(body) = &request.body {
+                    log::debug!(
+                        "resend request {} {}: {}",
+                        method,
+                        path,
+                        String::from_utf8_lossy(body)
+                    );
+                }
+            }
+            let started = Instant::now();
+            let result = next.run(request).await;
+            let elapsed = started.elapsed();
+            match &result {
+                Ok(response) => {
+                    log::debug!(
+                        "resend {} {} -> {} in {:?}",
+                        method,
+                        path,
+                        response.status,
+                        elapsed
+                    );
+                    if self.log_bodies {
+                        log::debug!(
+                            "resend response {} {}: {}",
+                            method,
+                            path,
+                            String::from_utf8_lossy(&response.body)
+                        );
+                    }
+                }
+                Err(err) => log::warn!(
+                    "resend {} {} failed in {:?}: {}",
+                    method,
+                    path,
+                    elapsed,
+                    err
+                ),
+            }
+            result
+        })
+    }
+}
+
+// adds headers to every request unless the request already sets them
+#[derive(Debug, Clone, Default)]
+pub struct DefaultHeaders {
+    pub headers: HeaderMap,
+}
+
+impl DefaultHeaders {
+    pub fn new() -> Self {
+        DefaultHeaders::default()
+    }
+
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+}
+
+impl Middleware for DefaultHeaders {
+    fn handle<'a>(&'a self, mut request: TransportRequest, next: Next<'a>) -> TransportFuture<'a> {
+        for (name, value) in self.headers.iter() {
+            if !request.headers.contains_key(name) {
+                request.headers.insert(name.clone(), value.clone());
+            }
+        }
+        next.run(request)
+    }
+}
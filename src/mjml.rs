@@ -0,0 +1,72 @@
+use std::fmt;
+use std::ops::Range;
+
+use mrml::prelude::parser::Error as ParserError;
+use mrml::prelude::render::RenderOptions;
+
+use crate::reqlib::ValidationError;
+
+// why a template did not compile, with the compiler's message and the byte range of the
+// offending part of the template when the compiler reports one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MjmlError {
+    pub message: String,
+    pub position: Option<Range<usize>>,
+}
+
+impl fmt::Display for MjmlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid MJML: {}", self.message)
+    }
+}
+
+impl std::error::Error for MjmlError {}
+
+// the html the template was meant to fill is what the API would reject
+impl From<MjmlError> for ValidationError {
+    fn from(err: MjmlError) -> Self {
+        ValidationError::new("html", err.to_string())
+    }
+}
+
+// compiles MJML into responsive html
+pub fn mjml_to_html(mjml: &str) -> Result<String, MjmlError> {
+    let parsed = mrml::parse(mjml).map_err(|err| MjmlError {
+        message: err.to_string(),
+        position: position(mjml, &err),
+    })?;
+    parsed
+        .element
+        .render(&RenderOptions::default())
+        .map_err(|err| MjmlError {
+            message: format!("could not render: {}", err),
+            position: None,
+        })
+}
+
+fn position(mjml: &str, err: &ParserError) -> Option<Range<usize>> {
+    match err {
+        ParserError::EndOfStream { .. } => Some(mjml.len()..mjml.len()),
+        // the tokenizer counts 1-based rows and columns in characters
+        ParserError::ParserError { source, .. } => {
+            let pos = source.pos();
+            let line_start = mjml
+                .split_inclusive('\n')
+                .take(pos.row.saturating_sub(1) as usize)
+                .map(str::len)
+                .sum::<usize>();
+            let offset = mjml[line_start..]
+                .char_indices()
+                .nth(pos.col.saturating_sub(1) as usize)
+                .map_or(mjml.len(), |(i, _)| line_start + i);
+            Some(offset..offset)
+        }
+        ParserError::UnexpectedElement { position, .. }
+        | ParserError::UnexpectedToken { position, .. }
+        | ParserError::MissingAttribute { position, .. }
+        | ParserError::InvalidAttribute { position, .. }
+        | ParserError::InvalidFormat { position, .. }
+        | ParserError::IncludeLoaderError { position, .. } => Some(position.start..position.end),
+        _ => None,
+    }
+}
@@ -0,0 +1,143 @@
+use std::time::Instant;
+
+use http::Method;
+use opentelemetry::metrics::{Counter, Gauge, Histogram, Meter};
+use opentelemetry::KeyValue;
+
+use crate::reqlib::{APIResponse, Error, RATE_LIMIT_REMAINING_HEADER};
+use crate::transport::{TransportRequest, TransportResponse};
+
+// instruments are created once from the caller's meter, e.g.
+// `opentelemetry::global::meter("resend")`, and shared by every service of a client
+#[derive(Debug, Clone)]
+pub struct Metrics {
+    requests: Counter<u64>,
+    latency: Histogram<f64>,
+    rate_limit_remaining: Gauge<i64>,
+    emails_sent: Counter<u64>,
+    recipients: Counter<u64>,
+}
+
+impl Metrics {
+    pub fn new(meter: &Meter) -> Self {
+        Metrics {
+            requests: meter
+                .u64_counter("resend.client.requests")
+                .with_description("Requests by endpoint and outcome")
+                .build(),
+            latency: meter
+                .f64_histogram("resend.client.request.duration")
+                .with_description("Time until the API responded")
+                .with_unit("s")
+                .build(),
+            rate_limit_remaining: meter
+                .i64_gauge("resend.client.rate_limit.remaining")
+                .with_description("Requests left in the current rate limit window")
+                .build(),
+            emails_sent: meter
+                .u64_counter("resend.client.emails.sent")
+                .with_description("Emails accepted by the API")
+                .build(),
+            recipients: meter
+                .u64_counter("resend.client.emails.recipients")
+                .with_description("Recipients of emails accepted by the API")
+                .build(),
+        }
+    }
+
+    // client-side rejections never reach `ReqClient::exec`, so they are counted separately
+    pub(crate) fn record_validation(&self, method: &Method, path: &str) {
+        self.requests.add(
+            1,
+            &[
+                method_attribute(method),
+                endpoint(path),
+                outcome("validation"),
+            ],
+        );
+    }
+
+    pub(crate) fn record_sent(&self, emails: u64, recipients: u64) {
+        self.emails_sent.add(emails, &[]);
+        self.recipients.add(recipients, &[]);
+    }
+}
+
+// measures a single `ReqClient::exec` call, a no-op when metrics are disabled
+pub(crate) struct Observation<'a> {
+    metrics: Option<&'a Metrics>,
+    attributes: [KeyValue; 2],
+    started: Instant,
+}
+
+impl<'a> Observation<'a> {
+    pub(crate) fn start(metrics: Option<&'a Metrics>, request: &TransportRequest) -> Self {
+        Observation {
+            metrics,
+            attributes: [method_attribute(&request.method), endpoint(request.path())],
+            started: Instant::now(),
+        }
+    }
+
+    pub(crate) fn response(&self, response: &TransportResponse) {
+        let metrics = match self.metrics {
+            Some(metrics) => metrics,
+            None => return,
+        };
+        if let Some(remaining) = response
+            .headers
+            .get(RATE_LIMIT_REMAINING_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<i64>().ok())
+        {
+            metrics
+                .rate_limit_remaining
+                .record(remaining, &[self.attributes[1].clone()]);
+        }
+    }
+
+    pub(crate) fn finish<T>(self, result: &Result<APIResponse<T>, APIResponse<T>>) {
+        let metrics = match self.metrics {
+            Some(metrics) => metrics,
+            None => return,
+        };
+        let response = match result {
+            Ok(response) | Err(response) => response,
+        };
+        let [method, endpoint] = self.attributes;
+        metrics.latency.record(
+            self.started.elapsed().as_secs_f64(),
+            &[method.clone(), endpoint.clone()],
+        );
+        let outcome = outcome(match response {
+            APIResponse::Success(_) => "success",
+            APIResponse::Error(_) => "error",
+            APIResponse::ParseError(_) => "parse_error",
+            APIResponse::Failure(Error::Validation(_)) => "validation",
+            APIResponse::Failure(_) => "failure",
+        });
+        metrics.requests.add(1, &[method, endpoint, outcome]);
+    }
+}
+
+fn method_attribute(method: &Method) -> KeyValue {
+    KeyValue::new("http.method", method.to_string())
+}
+
+fn outcome(outcome: &'static str) -> KeyValue {
+    KeyValue::new("outcome", outcome)
+}
+
+// ids are replaced so every email or domain shares one series, e.g. `/domains/{id}/verify`
+fn endpoint(path: &str) -> KeyValue {
+    let segments: Vec<&str> = path
+        .trim_matches('/')
+        .split('/')
+        .enumerate()
+        .map(|(index, segment)| match (index, segment) {
+            (0, segment) | (_, segment @ ("batch" | "verify")) => segment,
+            _ => "{id}",
+        })
+        .collect();
+    KeyValue::new("endpoint", format!("/{}", segments.join("/")))
+}
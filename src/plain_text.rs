@@ -0,0 +1,403 @@
+use crate::emails::{SendEmailRequest, SendInterceptor};
+use crate::reqlib::ValidationError;
+
+// elements whose content never ends up in the text
+const SKIPPED: [&str; 5] = ["head", "script", "style", "template", "noscript"];
+const BLOCKS: [&str; 12] = [
+    "address", "article", "aside", "center", "div", "footer", "header", "main", "nav", "section",
+    "table", "tbody",
+];
+const PARAGRAPHS: [&str; 10] = [
+    "blockquote",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "p",
+    "pre",
+];
+
+// fills `text` from `html` on every send that has html but no text
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TextAlternative;
+
+impl SendInterceptor for TextAlternative {
+    fn intercept(&self, email: &mut SendEmailRequest<'_>) -> Result<(), ValidationError> {
+        if email.text.is_none() {
+            email.text = email.html.as_deref().map(html_to_text);
+        }
+        Ok(())
+    }
+}
+
+// links become `text (url)`, list items bullets and table cells are joined with ` | `
+pub fn html_to_text(html: &str) -> String {
+    let mut writer = Writer::default();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        writer.text(&decode_entities(&rest[..start]));
+        rest = &rest[start..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let end = match tag_end(rest) {
+            Some(end) => end,
+            None => {
+                // a stray `<` that does not start a tag
+                writer.text("<");
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        let tag = Tag::parse(&rest[1..end]);
+        rest = &rest[end + 1..];
+        if let Some(tag) = tag {
+            if !tag.closing && !tag.self_closing && SKIPPED.contains(&tag.name.as_str()) {
+                rest = skip_element(rest, &tag.name);
+                continue;
+            }
+            writer.tag(&tag);
+        }
+    }
+    writer.text(&decode_entities(rest));
+    writer.finish()
+}
+
+struct Tag {
+    name: String,
+    closing: bool,
+    self_closing: bool,
+    attributes: String,
+}
+
+impl Tag {
+    // `None` for declarations such as `<!DOCTYPE html>`
+    fn parse(source: &str) -> Option<Tag> {
+        if source.starts_with('!') || source.starts_with('?') {
+            return None;
+        }
+        let (closing, source) = match source.strip_prefix('/') {
+            Some(source) => (true, source),
+            None => (false, source),
+        };
+        let self_closing = source.ends_with('/');
+        let source = source.trim_end_matches('/');
+        let name_end = source
+            .find(|c: char| c.is_whitespace())
+            .unwrap_or(source.len());
+        Some(Tag {
+            name: source[..name_end].to_ascii_lowercase(),
+            closing,
+            self_closing,
+            attributes: source[name_end..].to_string(),
+        })
+    }
+
+    fn attribute(&self, name: &str) -> Option<String> {
+        let mut rest = self.attributes.trim_start();
+        while !rest.is_empty() {
+            let key_end = rest
+                .find(|c: char| c == '=' || c.is_whitespace())
+                .unwrap_or(rest.len());
+            let key = &rest[..key_end];
+            rest = rest[key_end..].trim_start();
+            let mut value = "";
+            if let Some(after) = rest.strip_prefix('=') {
+                let after = after.trim_start();
+                let (found, remaining) = match after.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let inner = &after[1..];
+                        let end = inner.find(quote).unwrap_or(inner.len());
+                        (&inner[..end], inner.get(end + 1..).unwrap_or(""))
+                    }
+                    _ => {
+                        let end = after
+                            .find(|c: char| c.is_whitespace())
+                            .unwrap_or(after.len());
+                        (&after[..end], &after[end..])
+                    }
+                };
+                value = found;
+                rest = remaining.trim_start();
+            }
+            if key.eq_ignore_ascii_case(name) {
+                return Some(decode_entities(value));
+            }
+        }
+        None
+    }
+}
+
+// index of the `>` closing the tag at the start of `html`, skipping quoted attribute values
+fn tag_end(html: &str) -> Option<usize> {
+    let next = html[1..].chars().next()?;
+    if !(next.is_ascii_alphabetic() || next == '/' || next == '!' || next == '?') {
+        return None;
+    }
+    let mut quote = None;
+    for (index, c) in html.char_indices().skip(1) {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, '>') => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn skip_element<'a>(html: &'a str, name: &str) -> &'a str {
+    let close = format!("</{}", name);
+    match html.to_ascii_lowercase().find(&close) {
+        Some(start) => html[start..]
+            .find('>')
+            .map_or("", |end| &html[start + end + 1..]),
+        None => "",
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest[1..]
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| decode_entity(&rest[1..end + 1]).map(|c| (c, end + 2)));
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    if let Some(number) = entity.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    Some(match entity {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "hellip" => '…',
+        "mdash" => '—',
+        "ndash" => '–',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "bull" => '•',
+        "middot" => '·',
+        "euro" => '€',
+        _ => return None,
+    })
+}
+
+#[derive(Default)]
+struct Writer {
+    out: String,
+    pending_space: bool,
+    pre: usize,
+    // `None` for `ul`, the next number for `ol`
+    lists: Vec<Option<u32>>,
+    // output position where each open link's text starts, with its href
+    links: Vec<(usize, Option<String>)>,
+    // cells written so far in the current row of each open table
+    cells: Vec<usize>,
+}
+
+impl Writer {
+    fn text(&mut self, text: &str) {
+        for c in text.chars() {
+            if self.pre > 0 {
+                self.out.push(c);
+            } else if c.is_whitespace() {
+                self.pending_space = true;
+            } else {
+                if self.pending_space && !self.out.is_empty() && !self.out.ends_with([' ', '\n']) {
+                    self.out.push(' ');
+                }
+                self.pending_space = false;
+                self.out.push(c);
+            }
+        }
+    }
+
+    fn trim_trailing_spaces(&mut self) {
+        let len = self.out.trim_end_matches(' ').len();
+        self.out.truncate(len);
+        // links opened inside the trimmed spaces now start at the end of the text
+        for (start, _) in self.links.iter_mut() {
+            *start = (*start).min(len);
+        }
+        self.pending_space = false;
+    }
+
+    fn line_break(&mut self) {
+        self.trim_trailing_spaces();
+        self.out.push('\n');
+    }
+
+    fn newline(&mut self) {
+        self.trim_trailing_spaces();
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+    }
+
+    fn blank_line(&mut self) {
+        self.newline();
+        if !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+
+    fn tag(&mut self, tag: &Tag) {
+        let name = tag.name.as_str();
+        match (name, tag.closing) {
+            ("br", _) => self.line_break(),
+            ("hr", false) => {
+                self.blank_line();
+                self.out.push_str("---");
+                self.blank_line();
+            }
+            ("pre", false) => {
+                self.blank_line();
+                self.pre += 1;
+            }
+            ("pre", true) => {
+                self.pre = self.pre.saturating_sub(1);
+                self.blank_line();
+            }
+            ("ul", false) => {
+                self.newline();
+                self.lists.push(None);
+            }
+            ("ol", false) => {
+                self.newline();
+                let start = tag.attribute("start").and_then(|s| s.trim().parse().ok());
+                self.lists.push(Some(start.unwrap_or(1)));
+            }
+            ("ul" | "ol", true) => {
+                self.lists.pop();
+                self.newline();
+                if self.lists.is_empty() {
+                    self.blank_line();
+                }
+            }
+            ("li", false) => {
+                self.newline();
+                let depth = self.lists.len().max(1);
+                self.out.push_str(&"  ".repeat(depth - 1));
+                match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        self.out.push_str(&format!("{}. ", number));
+                        *number += 1;
+                    }
+                    _ => self.out.push_str("- "),
+                }
+            }
+            ("li", true) => self.newline(),
+            ("a", false) => {
+                self.links.push((self.out.len(), tag.attribute("href")));
+            }
+            ("a", true) => {
+                if let Some((start, Some(href))) = self.links.pop() {
+                    self.link_target(start, href.trim());
+                }
+            }
+            ("img", false) => {
+                if let Some(alt) = tag.attribute("alt").filter(|alt| !alt.trim().is_empty()) {
+                    self.text(" ");
+                    self.text(&alt);
+                    self.text(" ");
+                }
+            }
+            ("table", false) => {
+                self.newline();
+                self.cells.push(0);
+            }
+            ("table", true) => {
+                self.cells.pop();
+                self.blank_line();
+            }
+            ("tr", false) => {
+                self.newline();
+                if let Some(cells) = self.cells.last_mut() {
+                    *cells = 0;
+                }
+            }
+            ("tr", true) => self.newline(),
+            ("td" | "th", false) => {
+                let previous = self.cells.last().copied();
+                if previous.is_some_and(|cells| cells > 0) {
+                    self.trim_trailing_spaces();
+                    self.out.push_str(" | ");
+                }
+                if let Some(cells) = self.cells.last_mut() {
+                    *cells += 1;
+                }
+            }
+            _ if PARAGRAPHS.contains(&name) => self.blank_line(),
+            _ if BLOCKS.contains(&name) => self.newline(),
+            _ => {}
+        }
+    }
+
+    // appends ` (url)` unless it would only repeat the link text or point inside the document
+    fn link_target(&mut self, start: usize, href: &str) {
+        let text = self.out[start..].trim();
+        let bare = href.strip_prefix("mailto:").unwrap_or(href);
+        if href.is_empty() || href.starts_with('#') || href.starts_with("javascript:") {
+            return;
+        }
+        if text.is_empty() {
+            self.text(href);
+        } else if text != href && text != bare {
+            self.text(&format!(" ({})", href));
+        }
+    }
+
+    fn finish(self) -> String {
+        let mut text = String::with_capacity(self.out.len());
+        let mut blank_lines = 0;
+        for line in self.out.trim().lines() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                blank_lines += 1;
+                if blank_lines > 1 {
+                    continue;
+                }
+            } else {
+                blank_lines = 0;
+            }
+            text.push_str(line);
+            text.push('\n');
+        }
+        text.trim_end().to_string()
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use crate::dry_run::recipient_domain;
+use crate::emails::{SendEmailRequest, SendInterceptor};
+use crate::reqlib::ValidationError;
+
+pub const ORIGINAL_TO_HEADER: &str = "X-Original-To";
+pub const ORIGINAL_BCC_HEADER: &str = "X-Original-Bcc";
+
+// sends every email to a single inbox instead of its real recipients, for non-production
+// environments. recipients on an allowed domain keep receiving their copy.
+#[derive(Debug, Clone)]
+pub struct RecipientRedirect {
+    pub redirect_to: String,
+    pub subject_prefix: Option<String>,
+    pub allowed_domains: Vec<String>,
+}
+
+impl RecipientRedirect {
+    pub fn new<T: Into<String>>(redirect_to: T) -> Self {
+        RecipientRedirect {
+            redirect_to: redirect_to.into(),
+            subject_prefix: None,
+            allowed_domains: vec![],
+        }
+    }
+
+    pub fn subject_prefix<T: Into<String>>(mut self, prefix: T) -> Self {
+        self.subject_prefix = Some(prefix.into());
+        self
+    }
+
+    pub fn allow_domain<T: Into<String>>(mut self, domain: T) -> Self {
+        self.allowed_domains.push(domain.into());
+        self
+    }
+
+    fn is_allowed(&self, recipient: &str) -> bool {
+        recipient_domain(recipient).is_some_and(|domain| {
+            self.allowed_domains
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(&domain))
+        })
+    }
+}
+
+impl SendInterceptor for RecipientRedirect {
+    fn intercept(&self, email: &mut SendEmailRequest<'_>) -> Result<(), ValidationError> {
+        if let Some(prefix) = &self.subject_prefix {
+            if !email.subject.starts_with(prefix.as_str()) {
+                email.subject = format!("{} {}", prefix, email.subject);
+            }
+        }
+
+        if email
+            .recipients()
+            .all(|recipient| self.is_allowed(recipient))
+        {
+            return Ok(());
+        }
+        let originals: Vec<String> = email
+            .to
+            .iter()
+            .chain(email.cc.iter().flatten())
+            .cloned()
+            .collect();
+        let original_bcc = email.bcc.clone().unwrap_or_default();
+
+        email.to.retain(|recipient| self.is_allowed(recipient));
+        if !email.to.contains(&self.redirect_to) {
+            email.to.push(self.redirect_to.clone());
+        }
+        for field in [&mut email.cc, &mut email.bcc] {
+            if let Some(recipients) = field.as_mut() {
+                recipients.retain(|recipient| self.is_allowed(recipient));
+            }
+            if field
+                .as_ref()
+                .is_some_and(|recipients| recipients.is_empty())
+            {
+                *field = None;
+            }
+        }
+        let headers = email.headers.get_or_insert_with(HashMap::new);
+        headers.insert(ORIGINAL_TO_HEADER.to_string(), originals.join(", "));
+        // bcc addresses must stay hidden from everyone who still gets the email, so they are
+        // only kept when the redirect inbox is the only recipient left
+        let inbox_only = email.to.len() == 1 && email.cc.is_none() && email.bcc.is_none();
+        if inbox_only && !original_bcc.is_empty() {
+            headers.insert(ORIGINAL_BCC_HEADER.to_string(), original_bcc.join(", "));
+        }
+        Ok(())
+    }
+}
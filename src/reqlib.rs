@@ -1,13 +1,28 @@
 use const_format::concatcp;
-use reqwest::{header::HeaderValue, Error, RequestBuilder};
+use http::header::{self, HeaderMap, HeaderName, HeaderValue};
+use http::{Method, StatusCode};
 use serde::de::DeserializeOwned;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::AtomicU32;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use zeroize::Zeroizing;
 
-const VERSION: &'static str = "0.1.0";
-const DEFAULT_BASE_URL: &'static str = "https://api.resend.com";
-const USER_AGENT: &'static str = concatcp!("resend-rust/", VERSION);
-const CONTENT_TYPE: &'static str = "application/json";
+use crate::middleware::{Middleware, Next};
+use crate::secret::ApiKey;
+use crate::transport::{ReqwestTransport, Transport, TransportRequest, TransportResponse};
+
+const VERSION: &str = "0.1.0";
+pub(crate) const DEFAULT_BASE_URL: &str = "https://api.resend.com";
+pub(crate) const USER_AGENT: &str = concatcp!("resend-rust/", VERSION);
+pub(crate) const CONTENT_TYPE: &str = "application/json";
+// set by the API on every response, quote it when contacting Resend support
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+pub const RATE_LIMIT_LIMIT_HEADER: &str = "ratelimit-limit";
+pub const RATE_LIMIT_REMAINING_HEADER: &str = "ratelimit-remaining";
+pub const RATE_LIMIT_RESET_HEADER: &str = "ratelimit-reset";
 
 #[derive(Debug)]
 pub enum APIResponse<T> {
@@ -17,15 +32,186 @@ pub enum APIResponse<T> {
     Failure(Error),
 }
 
+// the result of a request together with what the API sent alongside it
+#[derive(Debug)]
+pub struct Response<T> {
+    pub result: Result<APIResponse<T>, APIResponse<T>>,
+    // `None` when nothing was received, e.g. transport failures, validation errors or dry runs
+    pub meta: Option<ResponseMeta>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResponseMeta {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub rate_limit: Option<RateLimitInfo>,
+    // quote this when contacting Resend support
+    pub request_id: Option<String>,
+    pub elapsed: Duration,
+}
+
+impl ResponseMeta {
+    pub fn new(status: StatusCode, headers: HeaderMap, elapsed: Duration) -> Self {
+        ResponseMeta {
+            status,
+            rate_limit: RateLimitInfo::from_headers(&headers),
+            request_id: header_str(&headers, REQUEST_ID_HEADER).map(String::from),
+            headers,
+            elapsed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitInfo {
+    // requests allowed per window
+    pub limit: u32,
+    pub remaining: u32,
+    // time until the window resets
+    pub reset: Duration,
+}
+
+impl RateLimitInfo {
+    // `None` unless all of the `ratelimit-*` headers are present and numeric
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let number = |name: &str| header_str(headers, name)?.trim().parse::<u64>().ok();
+        Some(RateLimitInfo {
+            limit: number(RATE_LIMIT_LIMIT_HEADER)?.try_into().ok()?,
+            remaining: number(RATE_LIMIT_REMAINING_HEADER)?.try_into().ok()?,
+            reset: Duration::from_secs(number(RATE_LIMIT_RESET_HEADER)?),
+        })
+    }
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub field: String,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new<F: Into<String>, M: Into<String>>(field: F, message: M) -> Self {
+        ValidationError {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid `{}`: {}", self.field, self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+#[derive(Debug)]
+pub enum Error {
+    // the request did not produce a response, e.g. a connection failure or timeout
+    Transport(Box<dyn std::error::Error + Send + Sync>),
+    // the API answered with an error status but without a Resend error body
+    Status {
+        status: StatusCode,
+        body: String,
+    },
+    // rejected client-side before anything was sent
+    Validation(ValidationError),
+    // the batch endpoint acknowledged a different number of emails than were posted
+    BatchMismatch {
+        sent: usize,
+        acknowledged: usize,
+    },
+    // a batch sent one email at a time, e.g. over SMTP, stopped at the email at `failed_at`.
+    // the emails before it were delivered as `delivered` and cannot be taken back
+    PartialBatch {
+        delivered: Vec<String>,
+        failed_at: usize,
+        error: ErrorResponse,
+    },
+}
+
+impl From<ValidationError> for Error {
+    fn from(err: ValidationError) -> Self {
+        Error::Validation(err)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Transport(err) => write!(f, "transport error: {}", err),
+            Error::Status { status, .. } => write!(f, "unexpected response status {}", status),
+            Error::Validation(err) => err.fmt(f),
+            Error::BatchMismatch { sent, acknowledged } => write!(
+                f,
+                "{} emails were sent but the API acknowledged {}",
+                sent, acknowledged
+            ),
+            Error::PartialBatch {
+                delivered,
+                failed_at,
+                error,
+            } => write!(
+                f,
+                "email {} of the batch failed with {}: {}, the emails before it were delivered as {}",
+                failed_at + 1,
+                error.name,
+                error.message,
+                delivered.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Transport(err) => Some(err.as_ref()),
+            Error::Validation(err) => Some(err),
+            Error::Status { .. } | Error::BatchMismatch { .. } | Error::PartialBatch { .. } => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Error::Transport(Box::new(err))
+    }
+}
+
 pub struct ReqClient {
-    pub client: reqwest::Client,
-    pub api_key: String,
+    pub transport: Arc<dyn Transport>,
+    pub api_key: ApiKey,
     pub base_url: url::Url,
     pub user_agent: String,
     pub headers: HashMap<String, String>,
+    // run in order around every request, see `Middleware`
+    pub middleware: Vec<Arc<dyn Middleware>>,
+    #[cfg(feature = "tracing")]
+    pub trace: crate::trace::TraceOptions,
+    #[cfg(feature = "otel")]
+    pub metrics: Option<Arc<crate::otel::Metrics>>,
+}
+
+// the transport and middleware are trait objects, so only their presence is shown
+impl fmt::Debug for ReqClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReqClient")
+            .field("api_key", &self.api_key)
+            .field("base_url", &self.base_url.as_str())
+            .field("user_agent", &self.user_agent)
+            .field("headers", &self.headers)
+            .field("middleware", &self.middleware.len())
+            .finish_non_exhaustive()
+    }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct ErrorResponse {
     pub name: String,
@@ -34,83 +220,205 @@ pub struct ErrorResponse {
 }
 
 impl ReqClient {
-    pub fn new(api_key: String) -> Self {
+    pub fn new<K: Into<ApiKey>>(api_key: K) -> Self {
         ReqClient::new_custom(reqwest::Client::new(), api_key)
     }
 
-    pub fn new_custom(http_client: reqwest::Client, api_key: String) -> Self {
-        let client = ReqClient {
-            client: http_client,
-            api_key: api_key,
+    pub fn new_custom<K: Into<ApiKey>>(http_client: reqwest::Client, api_key: K) -> Self {
+        ReqClient::with_transport(Arc::new(ReqwestTransport::new(http_client)), api_key)
+    }
+
+    pub fn with_transport<K: Into<ApiKey>>(transport: Arc<dyn Transport>, api_key: K) -> Self {
+        ReqClient {
+            transport,
+            api_key: api_key.into(),
             base_url: url::Url::parse(DEFAULT_BASE_URL).unwrap(),
             user_agent: USER_AGENT.to_string(),
             headers: HashMap::new(),
-        };
-        client
+            middleware: vec![],
+            #[cfg(feature = "tracing")]
+            trace: crate::trace::TraceOptions::default(),
+            #[cfg(feature = "otel")]
+            metrics: None,
+        }
+    }
+
+    pub fn add_middleware<M: Middleware + 'static>(&mut self, middleware: M) {
+        self.middleware.push(Arc::new(middleware));
     }
 
-    pub fn new_request(&self, method: reqwest::Method, path: &str) -> RequestBuilder {
+    pub fn new_request(&self, method: Method, path: &str) -> TransportRequest {
         let mut url = self.base_url.clone();
-        url.set_path(&path[..]);
-        let mut req = self.client.request(method, url);
-        // update request headers
-        for (key, value) in self.headers.iter() {
-            req = req.header(key, value);
+        url.set_path(path);
+        TransportRequest {
+            method,
+            url,
+            // invalid headers are reported by `exec`, like reqwest reports them on send
+            headers: request_headers(&self.headers, &self.user_agent, &self.api_key)
+                .unwrap_or_default(),
+            body: None,
         }
-        req = req.header(
-            reqwest::header::ACCEPT,
-            HeaderValue::from_static(CONTENT_TYPE),
-        );
-        req = req.header(
-            reqwest::header::USER_AGENT,
-            HeaderValue::from_bytes(&self.user_agent.as_bytes()).unwrap(),
-        );
-        req = req.header(
-            reqwest::header::AUTHORIZATION,
-            HeaderValue::from_bytes(format!("Bearer {}", self.api_key).as_bytes()).unwrap(),
-        );
-
-        req
     }
 
     pub fn new_body_request<T: serde::Serialize + ?Sized>(
         &self,
-        method: reqwest::Method,
+        method: Method,
         path: &str,
         json: Option<&T>,
-    ) -> RequestBuilder {
+    ) -> TransportRequest {
         let mut req = self.new_request(method, path);
         // update request body
-        if json.is_some() {
-            req = req.header(reqwest::header::CONTENT_TYPE, CONTENT_TYPE);
-            req = req.body(serde_json::to_string(&json.unwrap()).unwrap());
+        if let Some(json) = json {
+            req.headers
+                .insert(header::CONTENT_TYPE, HeaderValue::from_static(CONTENT_TYPE));
+            req.body = Some(serde_json::to_vec(json).unwrap());
         }
         req
     }
 
-    pub async fn exec<'a, T: DeserializeOwned>(
+    // calls an endpoint that has no service yet, with the same headers, middleware and error
+    // handling as the built-in services
+    pub async fn request<Req: Serialize + ?Sized, Resp: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&Req>,
+    ) -> Result<APIResponse<Resp>, APIResponse<Resp>> {
+        self.request_with_meta(method, path, query, body)
+            .await
+            .result
+    }
+
+    pub async fn request_with_meta<Req: Serialize + ?Sized, Resp: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&Req>,
+    ) -> Response<Resp> {
+        let mut req = self.new_body_request(method, path, body);
+        if !query.is_empty() {
+            req.url.query_pairs_mut().extend_pairs(query);
+        }
+        self.exec_with_meta(req).await
+    }
+
+    pub async fn exec<T: DeserializeOwned>(
         &self,
-        req: RequestBuilder,
+        req: TransportRequest,
     ) -> Result<APIResponse<T>, APIResponse<T>> {
-        let result = req.send().await;
-        match result {
+        self.exec_with_meta(req).await.result
+    }
+
+    pub async fn exec_with_meta<T: DeserializeOwned>(&self, req: TransportRequest) -> Response<T> {
+        if let Err(err) = request_headers(&self.headers, &self.user_agent, &self.api_key) {
+            #[cfg(feature = "tracing")]
+            crate::trace::record_validation(&req.method, req.path(), &err, &self.trace);
+            return Response {
+                result: Err(APIResponse::Failure(err.into())),
+                meta: None,
+            };
+        }
+        #[cfg(feature = "otel")]
+        let observation = crate::otel::Observation::start(self.metrics.as_deref(), &req);
+        let started = Instant::now();
+        let (result, meta) = match self.run(req).await {
             Ok(response) => {
-                let maybe_err = response.error_for_status_ref().err();
-                let body = response.text().await.unwrap();
-                let error_response = serde_json::from_str::<ErrorResponse>(&body[..]);
-                let success_response = serde_json::from_str::<T>(&body[..]);
-                match maybe_err {
-                    None => match success_response {
-                        Ok(resp) => Ok(APIResponse::Success(resp)),
-                        Err(err) => Err(APIResponse::ParseError(err)),
-                    },
-                    Some(err) => match error_response {
-                        Ok(err_resp) => Err(APIResponse::Error(err_resp)),
-                        Err(_) => Err(APIResponse::Failure(err)),
-                    },
-                }
+                #[cfg(feature = "otel")]
+                observation.response(&response);
+                let result = parse_response(response.status, &response.body);
+                let meta = ResponseMeta::new(response.status, response.headers, started.elapsed());
+                (result, Some(meta))
             }
-            Err(err) => Err(APIResponse::Failure(err)),
+            Err(err) => (Err(APIResponse::Failure(err)), None),
+        };
+        #[cfg(feature = "otel")]
+        observation.finish(&result);
+        Response { result, meta }
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    async fn run(&self, req: TransportRequest) -> Result<TransportResponse, Error> {
+        let transport_calls = AtomicU32::new(0);
+        Next::new(&self.middleware, self.transport.as_ref(), &transport_calls)
+            .run(req)
+            .await
+    }
+
+    #[cfg(feature = "tracing")]
+    async fn run(&self, req: TransportRequest) -> Result<TransportResponse, Error> {
+        use std::sync::atomic::Ordering;
+        use tracing::Instrument;
+
+        let span = crate::trace::request_span(&req, &self.trace);
+        let transport_calls = AtomicU32::new(0);
+        let started = Instant::now();
+        let result = Next::new(&self.middleware, self.transport.as_ref(), &transport_calls)
+            .run(req)
+            .instrument(span.clone())
+            .await;
+        crate::trace::record_response(
+            &span,
+            &result,
+            started.elapsed(),
+            transport_calls.load(Ordering::SeqCst),
+            &self.trace,
+        );
+        result
+    }
+}
+
+// shared with the blocking client so both send the same headers. custom headers are appended,
+// the accept, user agent and authorization headers replace custom ones with the same name
+pub(crate) fn request_headers(
+    headers: &HashMap<String, String>,
+    user_agent: &str,
+    api_key: &ApiKey,
+) -> Result<HeaderMap, ValidationError> {
+    let invalid = |name: &str, err: &dyn fmt::Display| {
+        ValidationError::new("headers", format!("invalid header `{}`: {}", name, err))
+    };
+    let mut map = HeaderMap::new();
+    // update request headers
+    for (key, value) in headers.iter() {
+        let name = HeaderName::from_bytes(key.as_bytes()).map_err(|err| invalid(key, &err))?;
+        let value = HeaderValue::from_bytes(value.as_bytes()).map_err(|err| invalid(key, &err))?;
+        map.append(name, value);
+    }
+    map.insert(header::ACCEPT, HeaderValue::from_static(CONTENT_TYPE));
+    map.insert(
+        header::USER_AGENT,
+        HeaderValue::from_bytes(user_agent.as_bytes())
+            .map_err(|err| invalid("user-agent", &err))?,
+    );
+    let bearer = Zeroizing::new(format!("Bearer {}", api_key.expose()));
+    // the value is left out of the error so the key cannot leak through it
+    let mut authorization = HeaderValue::from_bytes(bearer.as_bytes())
+        .map_err(|_| invalid("authorization", &"the API key is not a valid header value"))?;
+    // keeps the key out of the `Debug` output of the header map
+    authorization.set_sensitive(true);
+    map.insert(header::AUTHORIZATION, authorization);
+    Ok(map)
+}
+
+// shared with the blocking client so both report errors the same way
+pub(crate) fn parse_response<T: DeserializeOwned>(
+    status: StatusCode,
+    body: &[u8],
+) -> Result<APIResponse<T>, APIResponse<T>> {
+    if status.is_success() {
+        match serde_json::from_slice::<T>(body) {
+            Ok(resp) => Ok(APIResponse::Success(resp)),
+            Err(err) => Err(APIResponse::ParseError(err)),
+        }
+    } else {
+        match serde_json::from_slice::<ErrorResponse>(body) {
+            Ok(err_resp) => Err(APIResponse::Error(err_resp)),
+            Err(_) => Err(APIResponse::Failure(Error::Status {
+                status,
+                body: String::from_utf8_lossy(body).into_owned(),
+            })),
         }
     }
 }
@@ -0,0 +1,108 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use zeroize::Zeroize;
+
+// env var read by `ApiKey::from_env`
+pub const API_KEY_ENV: &str = "RESEND_API_KEY";
+
+const REDACTED: &str = "[REDACTED]";
+const REDACTED_ADDRESS: &str = "redacted@example.com";
+
+// the API key never shows up in `Debug` or `Display` output and is wiped from memory on drop
+#[derive(Clone, PartialEq, Eq)]
+pub struct ApiKey(String);
+
+impl ApiKey {
+    pub fn new<T: Into<String>>(key: T) -> Self {
+        ApiKey(key.into())
+    }
+
+    pub fn from_env() -> Result<Self, env::VarError> {
+        env::var(API_KEY_ENV).map(ApiKey)
+    }
+
+    // e.g. a Docker or Kubernetes secret mounted as a file, surrounding whitespace is ignored
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut contents = fs::read_to_string(path)?;
+        let key = ApiKey::new(contents.trim());
+        contents.zeroize();
+        if key.0.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the API key file is empty",
+            ));
+        }
+        Ok(key)
+    }
+
+    pub(crate) fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Drop for ApiKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ApiKey").field(&REDACTED).finish()
+    }
+}
+
+impl fmt::Display for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl From<String> for ApiKey {
+    fn from(key: String) -> Self {
+        ApiKey(key)
+    }
+}
+
+impl From<&str> for ApiKey {
+    fn from(key: &str) -> Self {
+        ApiKey::new(key)
+    }
+}
+
+// replaces anything shaped like `local@domain.tld`, keeping display names such as `Name <...>`
+pub fn redact_addresses(value: &str) -> String {
+    let is_local = |c: char| c.is_ascii_alphanumeric() || "._%+-".contains(c);
+    let is_domain = |c: char| c.is_ascii_alphanumeric() || ".-".contains(c);
+    let chars: Vec<char> = value.chars().collect();
+    let mut out = String::with_capacity(value.len());
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '@' {
+            let mut start = i;
+            while start > 0 && is_local(chars[start - 1]) {
+                start -= 1;
+            }
+            let mut end = i + 1;
+            while end < chars.len() && is_domain(chars[end]) {
+                end += 1;
+            }
+            let domain: String = chars[i + 1..end].iter().collect();
+            let domain = domain.trim_end_matches('.');
+            if start < i && domain.contains('.') {
+                // the local part has already been copied to the output
+                out.truncate(out.len() - (i - start));
+                out.push_str(REDACTED_ADDRESS);
+                i = i + 1 + domain.len();
+                continue;
+            }
+        }
+        out.push(chars[i]);
+        i += 1;
+    }
+    out
+}
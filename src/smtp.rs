@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+use http::{header, HeaderMap, HeaderValue, Method, StatusCode};
+use lettre::address::{Address, Envelope};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::client::{Tls, TlsParameters};
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::emails::{Attachment, SendEmailRequest};
+use crate::eml::{split_address, to_eml};
+use crate::reqlib::{Error, ErrorResponse, ValidationError, CONTENT_TYPE};
+use crate::transport::{Transport, TransportFuture, TransportRequest, TransportResponse};
+
+pub const DEFAULT_SMTP_HOST: &str = "smtp.resend.com";
+pub const DEFAULT_SMTP_PORT: u16 = 587;
+// Resend takes the API key as the password, the username is always the same
+pub const SMTP_USERNAME: &str = "resend";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    // plaintext upgraded with STARTTLS, e.g. port 587
+    StartTls,
+    // TLS from the first byte, e.g. port 465
+    Tls,
+    // no encryption at all, only meant for local sinks and relays
+    None,
+}
+
+// delivers `emails` and `emails/batch` requests over SMTP, for networks where only the
+// submission port is open. the outcome is answered like the API would, so the services and
+// middleware work unchanged. every other endpoint answers 501 Not Implemented
+#[derive(Debug, Clone)]
+pub struct SmtpTransport {
+    pub host: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    pub timeout: Option<Duration>,
+}
+
+impl Default for SmtpTransport {
+    fn default() -> Self {
+        SmtpTransport::relay(DEFAULT_SMTP_HOST, DEFAULT_SMTP_PORT)
+    }
+}
+
+impl SmtpTransport {
+    pub fn new() -> Self {
+        SmtpTransport::default()
+    }
+
+    pub fn relay<H: Into<String>>(host: H, port: u16) -> Self {
+        SmtpTransport {
+            host: host.into(),
+            port,
+            security: SmtpSecurity::StartTls,
+            timeout: Some(Duration::from_secs(60)),
+        }
+    }
+
+    pub fn security(mut self, security: SmtpSecurity) -> Self {
+        self.security = security;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn mailer(&self, api_key: &str) -> Result<AsyncSmtpTransport<Tokio1Executor>, Error> {
+        let tls = match self.security {
+            SmtpSecurity::StartTls => Tls::Required(tls_parameters(&self.host)?),
+            SmtpSecurity::Tls => Tls::Wrapper(tls_parameters(&self.host)?),
+            SmtpSecurity::None => Tls::None,
+        };
+        Ok(
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(self.host.as_str())
+                .port(self.port)
+                .tls(tls)
+                .timeout(self.timeout)
+                .credentials(Credentials::new(
+                    SMTP_USERNAME.to_string(),
+                    api_key.to_string(),
+                ))
+                .build(),
+        )
+    }
+}
+
+impl Transport for SmtpTransport {
+    fn send(&self, request: TransportRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let path = request.url.path().trim_matches('/');
+            let batch = match (&request.method, path) {
+                (&Method::POST, "emails") => false,
+                (&Method::POST, "emails/batch") => true,
+                (method, path) => {
+                    return Ok(error(
+                        StatusCode::NOT_IMPLEMENTED,
+                        "not_supported",
+                        format!("{} /{} is not available over SMTP", method, path),
+                    ))
+                }
+            };
+            let api_key = match bearer_token(&request.headers) {
+                Some(api_key) => api_key,
+                None => {
+                    return Ok(error(
+                        StatusCode::UNAUTHORIZED,
+                        "missing_api_key",
+                        "Missing API key in the authorization header.",
+                    ))
+                }
+            };
+            let body = request.body.unwrap_or_default();
+            let emails = match batch {
+                true => serde_json::from_slice::<Vec<OutgoingEmail>>(&body),
+                false => serde_json::from_slice::<OutgoingEmail>(&body).map(|email| vec![email]),
+            };
+            let emails = match emails {
+                Ok(emails) => emails,
+                Err(err) => return Ok(rejected("body", err.to_string())),
+            };
+
+            // every email is checked before the first one goes out
+            let prepared = match emails.iter().map(prepare).collect::<Result<Vec<_>, _>>() {
+                Ok(prepared) => prepared,
+                Err(err) => return Ok(rejected(&err.field, err.message)),
+            };
+
+            // a batch is sent one email at a time and stops at the first failure
+            let mailer = self.mailer(&api_key)?;
+            let mut ids: Vec<String> = Vec::with_capacity(prepared.len());
+            for (envelope, message) in prepared.iter() {
+                let err = match mailer.send_raw(envelope, message).await {
+                    Ok(response) => {
+                        let id = queued_id(&response).or_else(|| message_id(message));
+                        ids.push(id.unwrap_or_default());
+                        continue;
+                    }
+                    Err(err) => err,
+                };
+                // the server answered, e.g. a refused recipient or a wrong API key
+                let (status, name) = match err.status().map(u16::from) {
+                    Some(code) => smtp_status(code, err.is_permanent()),
+                    None if ids.is_empty() => return Err(Error::Transport(Box::new(err))),
+                    None => (StatusCode::SERVICE_UNAVAILABLE, "smtp_error"),
+                };
+                if ids.is_empty() {
+                    return Ok(error(status, name, err.to_string()));
+                }
+                // delivered emails cannot be taken back, so their ids are part of the error
+                return Err(Error::PartialBatch {
+                    failed_at: ids.len(),
+                    delivered: ids,
+                    error: ErrorResponse {
+                        name: name.to_string(),
+                        status_code: status.as_u16(),
+                        message: err.to_string(),
+                    },
+                });
+            }
+            let body = match batch {
+                true => {
+                    json!({ "data": ids.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>() })
+                }
+                false => json!({ "id": ids[0] }),
+            };
+            Ok(respond(StatusCode::OK, &body))
+        })
+    }
+}
+
+// the request body `ReqClient` serialized, tags are not available over SMTP
+#[derive(Deserialize)]
+struct OutgoingEmail {
+    subject: String,
+    from: String,
+    to: Vec<String>,
+    cc: Option<Vec<String>>,
+    bcc: Option<Vec<String>>,
+    reply_to: Option<String>,
+    html: Option<String>,
+    text: Option<String>,
+    #[serde(default)]
+    attachments: Vec<Attachment>,
+    headers: Option<HashMap<String, String>>,
+}
+
+impl OutgoingEmail {
+    fn request(&self) -> SendEmailRequest<'_> {
+        SendEmailRequest {
+            subject: self.subject.clone(),
+            from: self.from.clone(),
+            to: self.to.clone(),
+            cc: self.cc.clone(),
+            bcc: self.bcc.clone(),
+            reply_to: self.reply_to.clone(),
+            html: self.html.clone(),
+            text: self.text.clone(),
+            tags: None,
+            attachments: match self.attachments.is_empty() {
+                true => None,
+                false => Some(self.attachments.iter().collect()),
+            },
+            headers: self.headers.clone(),
+        }
+    }
+}
+
+fn prepare(email: &OutgoingEmail) -> Result<(Envelope, Vec<u8>), ValidationError> {
+    let request = email.request();
+    let recipients = request
+        .recipients()
+        .map(|recipient| envelope_address("to", recipient))
+        .collect::<Result<Vec<_>, _>>()?;
+    let from = envelope_address("from", &request.from)?;
+    let envelope = Envelope::new(Some(from), recipients)
+        .map_err(|err| ValidationError::new("to", err.to_string()))?;
+    // bcc recipients are only part of the envelope
+    let message = to_eml(&SendEmailRequest {
+        bcc: None,
+        ..request
+    })?;
+    Ok((envelope, message))
+}
+
+// servers report the queued message differently, e.g. `Ok: queued as 4F1A2B3C` or
+// `OK id=1rABC-0001`, and some only answer `OK`
+fn queued_id(response: &lettre::transport::smtp::response::Response) -> Option<String> {
+    let reply = response.message().collect::<Vec<_>>().join(" ");
+    let words: Vec<&str> = reply.split_whitespace().collect();
+    let queued_as = words.windows(3).find_map(|window| {
+        match window[0].eq_ignore_ascii_case("queued") && window[1].eq_ignore_ascii_case("as") {
+            true => Some(window[2]),
+            false => None,
+        }
+    });
+    let id = queued_as
+        .or_else(|| {
+            words.iter().find_map(|word| {
+                word.strip_prefix("id=")
+                    .or_else(|| word.strip_prefix("ID="))
+            })
+        })?
+        .trim_matches(['<', '>']);
+    Some(id.to_string()).filter(|id| !id.is_empty())
+}
+
+// the Message-ID written by `to_eml`, used when the server does not report a queue id
+fn message_id(message: &[u8]) -> Option<String> {
+    let message = String::from_utf8_lossy(message);
+    let headers = message.split("\r\n\r\n").next()?;
+    headers.split("\r\n").find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        match name.eq_ignore_ascii_case("message-id") {
+            true => Some(value.trim().trim_matches(['<', '>']).to_string()),
+            false => None,
+        }
+    })
+}
+
+fn tls_parameters(host: &str) -> Result<TlsParameters, Error> {
+    TlsParameters::new(host.to_string()).map_err(|err| Error::Transport(Box::new(err)))
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<String> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    value.strip_prefix("Bearer ").map(str::to_string)
+}
+
+fn envelope_address(field: &str, value: &str) -> Result<Address, ValidationError> {
+    let (_, email) = split_address(value);
+    Address::from_str(email)
+        .map_err(|err| ValidationError::new(field, format!("invalid address `{}`: {}", email, err)))
+}
+
+// the status the API uses for the same kind of failure as the server's reply code
+fn smtp_status(code: u16, permanent: bool) -> (StatusCode, &'static str) {
+    match code {
+        535 => (StatusCode::UNAUTHORIZED, "invalid_api_key"),
+        421 | 450 | 451 | 452 => (StatusCode::TOO_MANY_REQUESTS, "rate_limit_exceeded"),
+        _ if permanent => (StatusCode::UNPROCESSABLE_ENTITY, "validation_error"),
+        _ => (StatusCode::SERVICE_UNAVAILABLE, "smtp_error"),
+    }
+}
+
+fn rejected<M: Into<String>>(field: &str, message: M) -> TransportResponse {
+    error(
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "validation_error",
+        format!("invalid `{}`: {}", field, message.into()),
+    )
+}
+
+fn error<M: Into<String>>(status: StatusCode, name: &str, message: M) -> TransportResponse {
+    let error = ErrorResponse {
+        name: name.to_string(),
+        status_code: status.as_u16(),
+        message: message.into(),
+    };
+    respond(status, &serde_json::to_value(error).unwrap_or_default())
+}
+
+fn respond(status: StatusCode, body: &serde_json::Value) -> TransportResponse {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(CONTENT_TYPE));
+    TransportResponse {
+        status,
+        headers,
+        body: body.to_string().into_bytes(),
+    }
+}
@@ -0,0 +1,34 @@
+use askama::Template;
+
+use super::{EmailPart, EmailTemplate, RenderedEmail, TemplateError, TemplateErrorKind};
+
+// implemented by an askama template whose fields are the context, the template renders the html.
+// askama checks variables at compile time, so only render errors are left at runtime
+pub trait AskamaEmail: Template {
+    fn subject(&self) -> String;
+
+    // e.g. a second askama template over the same fields
+    fn text(&self) -> askama::Result<Option<String>> {
+        Ok(None)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AskamaTemplate;
+
+impl<C: AskamaEmail> EmailTemplate<C> for AskamaTemplate {
+    fn render(&self, context: &C) -> Result<RenderedEmail, TemplateError> {
+        let error = |part, err: askama::Error| {
+            TemplateError::new(part, TemplateErrorKind::Render, err.to_string())
+        };
+        Ok(RenderedEmail {
+            subject: context.subject(),
+            html: Some(
+                context
+                    .render()
+                    .map_err(|err| error(EmailPart::Html, err))?,
+            ),
+            text: context.text().map_err(|err| error(EmailPart::Text, err))?,
+        })
+    }
+}
@@ -0,0 +1,88 @@
+use handlebars::{no_escape, Handlebars, RenderErrorReason};
+use serde::Serialize;
+
+use super::{EmailPart, EmailTemplate, RenderedEmail, TemplateError, TemplateErrorKind};
+
+pub struct HandlebarsTemplate {
+    // escaping is a registry setting, so the html part gets a registry of its own
+    html: Handlebars<'static>,
+    plain: Handlebars<'static>,
+    has_html: bool,
+    has_text: bool,
+}
+
+impl HandlebarsTemplate {
+    // syntax errors are reported here rather than on the first render
+    pub fn new(
+        subject: &str,
+        html: Option<&str>,
+        text: Option<&str>,
+    ) -> Result<Self, TemplateError> {
+        let mut template = HandlebarsTemplate {
+            html: Handlebars::new(),
+            plain: Handlebars::new(),
+            has_html: html.is_some(),
+            has_text: text.is_some(),
+        };
+        template.html.set_strict_mode(true);
+        template.plain.set_strict_mode(true);
+        template.plain.register_escape_fn(no_escape);
+        for (part, source) in [
+            (EmailPart::Subject, Some(subject)),
+            (EmailPart::Html, html),
+            (EmailPart::Text, text),
+        ] {
+            if let Some(source) = source {
+                template
+                    .registry_mut(part)
+                    .register_template_string(part.as_str(), source)
+                    .map_err(|err| {
+                        TemplateError::new(part, TemplateErrorKind::Syntax, err.to_string())
+                    })?;
+            }
+        }
+        Ok(template)
+    }
+
+    // the registry of a part can be used to register helpers and partials
+    pub fn registry_mut(&mut self, part: EmailPart) -> &mut Handlebars<'static> {
+        match part {
+            EmailPart::Html => &mut self.html,
+            EmailPart::Subject | EmailPart::Text => &mut self.plain,
+        }
+    }
+
+    fn render_part<C: Serialize + ?Sized>(
+        &self,
+        part: EmailPart,
+        context: &C,
+    ) -> Result<String, TemplateError> {
+        let registry = match part {
+            EmailPart::Html => &self.html,
+            EmailPart::Subject | EmailPart::Text => &self.plain,
+        };
+        registry.render(part.as_str(), &context).map_err(|err| {
+            let kind = match err.reason() {
+                RenderErrorReason::MissingVariable(_) => TemplateErrorKind::MissingVariable,
+                _ => TemplateErrorKind::Render,
+            };
+            TemplateError::new(part, kind, err.to_string())
+        })
+    }
+}
+
+impl<C: Serialize + ?Sized> EmailTemplate<C> for HandlebarsTemplate {
+    fn render(&self, context: &C) -> Result<RenderedEmail, TemplateError> {
+        Ok(RenderedEmail {
+            subject: self.render_part(EmailPart::Subject, context)?,
+            html: match self.has_html {
+                true => Some(self.render_part(EmailPart::Html, context)?),
+                false => None,
+            },
+            text: match self.has_text {
+                true => Some(self.render_part(EmailPart::Text, context)?),
+                false => None,
+            },
+        })
+    }
+}
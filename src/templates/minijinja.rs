@@ -0,0 +1,87 @@
+use minijinja::value::Serde;
+use minijinja::{Environment, ErrorKind, UndefinedBehavior};
+use serde::Serialize;
+
+use super::{EmailPart, EmailTemplate, RenderedEmail, TemplateError, TemplateErrorKind};
+
+// template names double as the auto-escape setting, only the html part is escaped
+const SUBJECT: &str = "subject.txt";
+const HTML: &str = "body.html";
+const TEXT: &str = "body.txt";
+
+pub struct MiniJinjaTemplate {
+    env: Environment<'static>,
+    has_html: bool,
+    has_text: bool,
+}
+
+impl MiniJinjaTemplate {
+    // syntax errors are reported here rather than on the first render
+    pub fn new(
+        subject: &str,
+        html: Option<&str>,
+        text: Option<&str>,
+    ) -> Result<Self, TemplateError> {
+        let mut env = Environment::new();
+        env.set_undefined_behavior(UndefinedBehavior::Strict);
+        let has_html = html.is_some();
+        let has_text = text.is_some();
+        for (part, name, source) in [
+            (EmailPart::Subject, SUBJECT, Some(subject)),
+            (EmailPart::Html, HTML, html),
+            (EmailPart::Text, TEXT, text),
+        ] {
+            if let Some(source) = source {
+                env.add_template_owned(name, source.to_string())
+                    .map_err(|err| error(part, err))?;
+            }
+        }
+        Ok(MiniJinjaTemplate {
+            env,
+            has_html,
+            has_text,
+        })
+    }
+
+    // the environment can be used to register filters and functions
+    pub fn env_mut(&mut self) -> &mut Environment<'static> {
+        &mut self.env
+    }
+
+    fn render_part<C: Serialize + ?Sized>(
+        &self,
+        part: EmailPart,
+        name: &str,
+        context: &C,
+    ) -> Result<String, TemplateError> {
+        self.env
+            .get_template(name)
+            .and_then(|template| template.render(Serde(context)))
+            .map_err(|err| error(part, err))
+    }
+}
+
+impl<C: Serialize + ?Sized> EmailTemplate<C> for MiniJinjaTemplate {
+    fn render(&self, context: &C) -> Result<RenderedEmail, TemplateError> {
+        Ok(RenderedEmail {
+            subject: self.render_part(EmailPart::Subject, SUBJECT, context)?,
+            html: match self.has_html {
+                true => Some(self.render_part(EmailPart::Html, HTML, context)?),
+                false => None,
+            },
+            text: match self.has_text {
+                true => Some(self.render_part(EmailPart::Text, TEXT, context)?),
+                false => None,
+            },
+        })
+    }
+}
+
+fn error(part: EmailPart, err: minijinja::Error) -> TemplateError {
+    let kind = match err.kind() {
+        ErrorKind::UndefinedError => TemplateErrorKind::MissingVariable,
+        ErrorKind::SyntaxError => TemplateErrorKind::Syntax,
+        _ => TemplateErrorKind::Render,
+    };
+    TemplateError::new(part, kind, err.to_string())
+}
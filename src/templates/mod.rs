@@ -0,0 +1,106 @@
+#[cfg(feature = "askama")]
+pub mod askama;
+#[cfg(feature = "handlebars")]
+pub mod handlebars;
+#[cfg(feature = "minijinja")]
+pub mod minijinja;
+
+use std::fmt;
+
+use crate::emails::SendEmailRequest;
+
+// which part of the email a template renders
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailPart {
+    Subject,
+    Html,
+    Text,
+}
+
+impl EmailPart {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmailPart::Subject => "subject",
+            EmailPart::Html => "html",
+            EmailPart::Text => "text",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateErrorKind {
+    // the context does not provide a variable the template uses
+    MissingVariable,
+    Syntax,
+    Render,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateError {
+    pub part: EmailPart,
+    pub kind: TemplateErrorKind,
+    pub message: String,
+}
+
+impl TemplateError {
+    pub fn new<M: Into<String>>(part: EmailPart, kind: TemplateErrorKind, message: M) -> Self {
+        TemplateError {
+            part,
+            kind,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} template: {}", self.part.as_str(), self.message)
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedEmail {
+    pub subject: String,
+    pub html: Option<String>,
+    pub text: Option<String>,
+}
+
+impl RenderedEmail {
+    // everything besides the rendered parts is left empty, to be filled in by the caller
+    pub fn into_request<'a, F: Into<String>>(
+        self,
+        from: F,
+        to: Vec<String>,
+    ) -> SendEmailRequest<'a> {
+        SendEmailRequest {
+            subject: self.subject,
+            from: from.into(),
+            to,
+            cc: None,
+            bcc: None,
+            reply_to: None,
+            html: self.html,
+            text: self.text,
+            tags: None,
+            attachments: None,
+            headers: None,
+        }
+    }
+}
+
+// renders subject, html and text from one context value, failing on variables it does not provide
+pub trait EmailTemplate<C: ?Sized> {
+    fn render(&self, context: &C) -> Result<RenderedEmail, TemplateError>;
+
+    fn render_request<'a, F: Into<String>>(
+        &self,
+        context: &C,
+        from: F,
+        to: Vec<String>,
+    ) -> Result<SendEmailRequest<'a>, TemplateError> {
+        self.render(context)
+            .map(|rendered| rendered.into_request(from, to))
+    }
+}
@@ -0,0 +1,491 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use http::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use http::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::domains::{
+    DnsRecord, DnsRecordType, DomainDetails, DomainStatus, EmailDnsRecord, Region,
+};
+use crate::emails::Email;
+use crate::reqlib::{
+    ErrorResponse, RATE_LIMIT_LIMIT_HEADER, RATE_LIMIT_REMAINING_HEADER, RATE_LIMIT_RESET_HEADER,
+    REQUEST_ID_HEADER,
+};
+use crate::transport::{Transport, TransportFuture, TransportRequest, TransportResponse};
+use crate::Client;
+
+pub const TEST_API_KEY: &str = "re_test_key";
+
+// the JSON body of a `POST /emails` call as it reached the fake
+#[derive(Debug, Clone, Deserialize)]
+pub struct SentEmail {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub created_at: String,
+    pub from: String,
+    pub to: Vec<String>,
+    pub subject: String,
+    pub cc: Option<Vec<String>>,
+    pub bcc: Option<Vec<String>>,
+    pub reply_to: Option<String>,
+    pub html: Option<String>,
+    pub text: Option<String>,
+    pub headers: Option<HashMap<String, String>>,
+    #[serde(skip)]
+    pub raw: serde_json::Value,
+}
+
+#[derive(Debug, Clone)]
+struct InjectedError {
+    method: Method,
+    path: String,
+    status: StatusCode,
+    error: ErrorResponse,
+}
+
+#[derive(Debug, Clone)]
+struct RateLimit {
+    limit: u32,
+    used: u32,
+}
+
+#[derive(Default)]
+struct State {
+    next_id: u64,
+    emails: Vec<SentEmail>,
+    domains: Vec<DomainDetails>,
+    errors: VecDeque<InjectedError>,
+    rate_limit: Option<RateLimit>,
+    requests: Vec<TransportRequest>,
+}
+
+// an in-process stand-in for the Resend API, plugged in as the client's transport
+#[derive(Default)]
+pub struct FakeResend {
+    state: Mutex<State>,
+}
+
+impl FakeResend {
+    pub fn new() -> Arc<FakeResend> {
+        Arc::new(FakeResend::default())
+    }
+
+    pub fn client(self: &Arc<Self>) -> Client {
+        Client::with_transport(TEST_API_KEY, self.clone())
+    }
+
+    pub fn sent_emails(&self) -> Vec<SentEmail> {
+        self.state.lock().unwrap().emails.clone()
+    }
+
+    pub fn domains(&self) -> Vec<DomainDetails> {
+        self.state.lock().unwrap().domains.clone()
+    }
+
+    pub fn requests(&self) -> Vec<TransportRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    pub fn clear(&self) {
+        *self.state.lock().unwrap() = State::default();
+    }
+
+    // the next request matching `method` and `path` (e.g. "emails") fails with this error
+    pub fn fail_next<T: Into<String>>(
+        &self,
+        method: Method,
+        path: &str,
+        status: StatusCode,
+        message: T,
+    ) {
+        let name = status
+            .canonical_reason()
+            .unwrap_or("error")
+            .to_ascii_lowercase()
+            .replace(' ', "_");
+        self.state.lock().unwrap().errors.push_back(InjectedError {
+            method,
+            path: path.trim_matches('/').to_string(),
+            status,
+            error: ErrorResponse {
+                name,
+                status_code: status.as_u16(),
+                message: message.into(),
+            },
+        });
+    }
+
+    // allow `limit` requests until `reset_rate_limit` is called, then answer 429
+    pub fn set_rate_limit(&self, limit: u32) {
+        self.state.lock().unwrap().rate_limit = Some(RateLimit { limit, used: 0 });
+    }
+
+    pub fn reset_rate_limit(&self) {
+        if let Some(rate_limit) = self.state.lock().unwrap().rate_limit.as_mut() {
+            rate_limit.used = 0;
+        }
+    }
+
+    // moves a domain and all of its records to `status`, e.g. to finish a verification
+    pub fn set_domain_status(&self, domain_id: &str, status: DomainStatus) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(domain) = state.domains.iter_mut().find(|d| d.id == domain_id) {
+            domain.status = status.clone();
+            for record in domain.records.iter_mut() {
+                record.status = status.clone();
+            }
+        }
+    }
+
+    fn handle(&self, request: &TransportRequest) -> TransportResponse {
+        let mut state = self.state.lock().unwrap();
+        state.requests.push(request.clone());
+        let path = request.url.path().trim_matches('/').to_string();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            REQUEST_ID_HEADER,
+            HeaderValue::from_str(&format!("req_{:012x}", state.requests.len())).unwrap(),
+        );
+        if let Some(rate_limit) = state.rate_limit.as_mut() {
+            rate_limit.used += 1;
+            let remaining = rate_limit.limit.saturating_sub(rate_limit.used);
+            insert_header(&mut headers, RATE_LIMIT_LIMIT_HEADER, rate_limit.limit);
+            insert_header(&mut headers, RATE_LIMIT_REMAINING_HEADER, remaining);
+            insert_header(&mut headers, RATE_LIMIT_RESET_HEADER, 1);
+            if rate_limit.used > rate_limit.limit {
+                insert_header(&mut headers, "retry-after", 1);
+                return error(
+                    headers,
+                    StatusCode::TOO_MANY_REQUESTS,
+                    "rate_limit_exceeded",
+                    "Too many requests. Please limit the number of requests per second.",
+                );
+            }
+        }
+
+        if let Some(index) = state
+            .errors
+            .iter()
+            .position(|err| err.method == request.method && err.path == path)
+        {
+            let injected = state.errors.remove(index).unwrap();
+            return respond(headers, injected.status, &injected.error);
+        }
+
+        let segments: Vec<&str> = path.split('/').collect();
+        match (&request.method, &segments[..]) {
+            (&Method::POST, ["emails"]) => {
+                let raw = match parse_body(request) {
+                    Ok(raw) => raw,
+                    Err(err) => return invalid_json(headers, err),
+                };
+                match state.store_email(raw) {
+                    Ok(id) => respond(headers, StatusCode::OK, &json!({ "id": id })),
+                    Err(err) => invalid_email(headers, err),
+                }
+            }
+            (&Method::POST, ["emails", "batch"]) => {
+                let raw: Vec<serde_json::Value> = match parse_body(request) {
+                    Ok(raw) => raw,
+                    Err(err) => return invalid_json(headers, err),
+                };
+                // validate the whole batch first, nothing is stored when one email is invalid
+                if let Some(err) = raw
+                    .iter()
+                    .find_map(|email| serde_json::from_value::<SentEmail>(email.clone()).err())
+                {
+                    return invalid_email(headers, err);
+                }
+                let data: Vec<serde_json::Value> = raw
+                    .into_iter()
+                    .map(|email| json!({ "id": state.store_email(email).unwrap() }))
+                    .collect();
+                respond(headers, StatusCode::OK, &json!({ "data": data }))
+            }
+            (&Method::GET, ["emails"]) => {
+                let data: Vec<Email> = state.emails.iter().rev().map(to_email).collect();
+                respond(
+                    headers,
+                    StatusCode::OK,
+                    &json!({ "object": "list", "data": data }),
+                )
+            }
+            (&Method::GET, ["emails", id]) => match state.emails.iter().find(|e| e.id == *id) {
+                Some(email) => respond(headers, StatusCode::OK, &to_email(email)),
+                None => not_found(headers, "Email not found"),
+            },
+            (&Method::POST, ["domains"]) => {
+                let raw: serde_json::Value = match parse_body(request) {
+                    Ok(raw) => raw,
+                    Err(err) => return invalid_json(headers, err),
+                };
+                let name = match raw["name"].as_str() {
+                    Some(name) => name.to_string(),
+                    None => {
+                        return error(
+                            headers,
+                            StatusCode::UNPROCESSABLE_ENTITY,
+                            "missing_required_field",
+                            "Missing `name` field.",
+                        )
+                    }
+                };
+                if state.domains.iter().any(|d| d.name == name) {
+                    return error(
+                        headers,
+                        StatusCode::FORBIDDEN,
+                        "validation_error",
+                        &format!("The {} domain has been registered already.", name),
+                    );
+                }
+                let region = raw["region"]
+                    .as_str()
+                    .map(Region::from)
+                    .unwrap_or(Region::UsEast1);
+                let domain = DomainDetails {
+                    id: state.next_id(),
+                    object: "domain".into(),
+                    records: domain_records(&region),
+                    name,
+                    created_at: timestamp(),
+                    status: DomainStatus::NotStarted,
+                    region,
+                };
+                let body = json!({
+                    "id": domain.id,
+                    "name": domain.name,
+                    "created_at": domain.created_at,
+                    "status": domain.status,
+                    "region": domain.region,
+                    "records": domain.records,
+                    "dnsProvider": "Unidentified",
+                });
+                state.domains.push(domain);
+                respond(headers, StatusCode::OK, &body)
+            }
+            (&Method::GET, ["domains"]) => {
+                let data: Vec<serde_json::Value> = state
+                    .domains
+                    .iter()
+                    .map(|domain| {
+                        json!({
+                            "id": domain.id,
+                            "name": domain.name,
+                            "status": domain.status,
+                            "created_at": domain.created_at,
+                            "region": domain.region,
+                        })
+                    })
+                    .collect();
+                respond(headers, StatusCode::OK, &json!({ "data": data }))
+            }
+            (&Method::GET, ["domains", id]) => match state.domains.iter().find(|d| d.id == *id) {
+                Some(domain) => respond(headers, StatusCode::OK, domain),
+                None => not_found(headers, "Domain not found"),
+            },
+            (&Method::POST, ["domains", id]) => {
+                match state.domains.iter_mut().find(|d| d.id == *id) {
+                    Some(domain) => {
+                        if domain.status != DomainStatus::Verified {
+                            domain.status = DomainStatus::Pending;
+                            for record in domain.records.iter_mut() {
+                                record.status = DomainStatus::Pending;
+                            }
+                        }
+                        let body = json!({ "object": "domain", "id": domain.id });
+                        respond(headers, StatusCode::OK, &body)
+                    }
+                    None => not_found(headers, "Domain not found"),
+                }
+            }
+            (&Method::DELETE, ["domains", id]) => {
+                match state.domains.iter().position(|d| d.id == *id) {
+                    Some(index) => {
+                        let domain = state.domains.remove(index);
+                        let body = json!({ "object": "domain", "id": domain.id, "deleted": true });
+                        respond(headers, StatusCode::OK, &body)
+                    }
+                    None => not_found(headers, "Domain not found"),
+                }
+            }
+            _ => error(
+                headers,
+                StatusCode::METHOD_NOT_ALLOWED,
+                "method_not_allowed",
+                "Method is not allowed for the requested path.",
+            ),
+        }
+    }
+}
+
+impl Transport for FakeResend {
+    fn send(&self, request: TransportRequest) -> TransportFuture<'_> {
+        let response = self.handle(&request);
+        Box::pin(async move { Ok(response) })
+    }
+}
+
+impl State {
+    fn next_id(&mut self) -> String {
+        self.next_id += 1;
+        format!("00000000-0000-4000-8000-{:012x}", self.next_id)
+    }
+
+    fn store_email(&mut self, raw: serde_json::Value) -> Result<String, serde_json::Error> {
+        let mut email: SentEmail = serde_json::from_value(raw.clone())?;
+        email.id = self.next_id();
+        email.created_at = timestamp();
+        email.raw = raw;
+        let id = email.id.clone();
+        self.emails.push(email);
+        Ok(id)
+    }
+}
+
+fn to_email(email: &SentEmail) -> Email {
+    Email {
+        id: email.id.clone(),
+        object: "email".into(),
+        from: email.from.clone(),
+        to: email.to.clone(),
+        created_at: email.created_at.clone(),
+        subject: email.subject.clone(),
+        html: email.html.clone(),
+        text: email.text.clone(),
+        bcc: email
+            .bcc
+            .as_ref()
+            .map(|bcc| bcc.iter().cloned().map(Some).collect()),
+        cc: email
+            .cc
+            .as_ref()
+            .map(|cc| cc.iter().cloned().map(Some).collect()),
+        reply_to: email.reply_to.clone().map(|reply_to| vec![Some(reply_to)]),
+        last_event: "delivered".into(),
+    }
+}
+
+fn domain_records(region: &Region) -> Vec<DnsRecord> {
+    let record = |record, r#type, name: &str, value: String, priority| DnsRecord {
+        record,
+        r#type,
+        name: name.into(),
+        ttl: "Auto".into(),
+        status: DomainStatus::NotStarted,
+        value,
+        priority,
+    };
+    vec![
+        record(
+            EmailDnsRecord::Spf,
+            DnsRecordType::Mx,
+            "send",
+            format!("feedback-smtp.{}.amazonses.com", region),
+            Some(10),
+        ),
+        record(
+            EmailDnsRecord::Spf,
+            DnsRecordType::Txt,
+            "send",
+            "\"v=spf1 include:amazonses.com ~all\"".into(),
+            None,
+        ),
+        record(
+            EmailDnsRecord::Dkim,
+            DnsRecordType::Txt,
+            "resend._domainkey",
+            "p=MIGfMA0GCSqGSIb3DQEBAQUAA4GNADCBiQKBgQDfake".into(),
+            None,
+        ),
+    ]
+}
+
+fn parse_body<T: serde::de::DeserializeOwned>(
+    request: &TransportRequest,
+) -> Result<T, serde_json::Error> {
+    serde_json::from_slice(request.body.as_deref().unwrap_or_default())
+}
+
+fn invalid_json(headers: HeaderMap, err: serde_json::Error) -> TransportResponse {
+    error(
+        headers,
+        StatusCode::BAD_REQUEST,
+        "invalid_json",
+        &err.to_string(),
+    )
+}
+
+fn invalid_email(headers: HeaderMap, err: serde_json::Error) -> TransportResponse {
+    error(
+        headers,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "validation_error",
+        &err.to_string(),
+    )
+}
+
+fn respond<T: Serialize + ?Sized>(
+    mut headers: HeaderMap,
+    status: StatusCode,
+    body: &T,
+) -> TransportResponse {
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    TransportResponse {
+        status,
+        headers,
+        body: serde_json::to_vec(body).unwrap(),
+    }
+}
+
+fn error(headers: HeaderMap, status: StatusCode, name: &str, message: &str) -> TransportResponse {
+    respond(
+        headers,
+        status,
+        &ErrorResponse {
+            name: name.into(),
+            status_code: status.as_u16(),
+            message: message.into(),
+        },
+    )
+}
+
+fn not_found(headers: HeaderMap, message: &str) -> TransportResponse {
+    error(headers, StatusCode::NOT_FOUND, "not_found", message)
+}
+
+fn insert_header(headers: &mut HeaderMap, name: &'static str, value: u32) {
+    headers.insert(HeaderName::from_static(name), HeaderValue::from(value));
+}
+
+// RFC 3339 in UTC without pulling in a date library
+fn timestamp() -> String {
+    let elapsed = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    let secs = elapsed.as_secs();
+    let (days, rem) = ((secs / 86_400) as i64, secs % 86_400);
+    // civil-from-days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60,
+        elapsed.subsec_millis()
+    )
+}
@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use http::Method;
+use tracing::field::Empty;
+use tracing::Span;
+
+use crate::reqlib::{Error, ErrorResponse, ValidationError, REQUEST_ID_HEADER};
+use crate::secret::redact_addresses;
+use crate::transport::{TransportRequest, TransportResponse};
+
+// what ends up on the `resend.request` span. the API key is never recorded; addresses are
+// redacted and bodies left out unless opted in, which is only meant for development. recorded
+// bodies have their addresses redacted too unless `record_recipients` is set
+#[derive(Debug, Clone, Default)]
+pub struct TraceOptions {
+    pub record_recipients: bool,
+    pub record_bodies: bool,
+}
+
+impl TraceOptions {
+    pub fn new() -> Self {
+        TraceOptions::default()
+    }
+
+    pub fn with_recipients(mut self) -> Self {
+        self.record_recipients = true;
+        self
+    }
+
+    pub fn with_bodies(mut self) -> Self {
+        self.record_bodies = true;
+        self
+    }
+}
+
+pub(crate) fn request_span(request: &TransportRequest, options: &TraceOptions) -> Span {
+    let span = tracing::info_span!(
+        "resend.request",
+        http.method = %request.method,
+        url.path = request.path(),
+        http.status_code = Empty,
+        latency_ms = Empty,
+        transport_calls = Empty,
+        resend.request_id = Empty,
+        recipients = Empty,
+        request.body = Empty,
+        response.body = Empty,
+        error = Empty,
+    );
+    if let Some(recipients) = recipients(request, options) {
+        span.record("recipients", recipients.as_str());
+    }
+    if options.record_bodies {
+        if let Some(body) = &request.body {
+            span.record("request.body", redact_body(body, options).as_str());
+        }
+    }
+    span
+}
+
+// client-side rejections never reach the transport, their span only carries the error
+pub(crate) fn record_validation(
+    method: &Method,
+    path: &str,
+    err: &ValidationError,
+    options: &TraceOptions,
+) {
+    let _span = tracing::info_span!(
+        "resend.request",
+        http.method = %method,
+        url.path = format!("/{}", path.trim_start_matches('/')),
+        error = redact(&err.to_string(), options),
+    );
+}
+
+pub(crate) fn record_response(
+    span: &Span,
+    result: &Result<TransportResponse, Error>,
+    latency: Duration,
+    transport_calls: u32,
+    options: &TraceOptions,
+) {
+    span.record("latency_ms", latency.as_millis() as u64);
+    span.record("transport_calls", transport_calls);
+    let response = match result {
+        Ok(response) => response,
+        Err(err) => {
+            span.record("error", redact(&err.to_string(), options).as_str());
+            return;
+        }
+    };
+    span.record("http.status_code", response.status.as_u16());
+    if let Some(id) = response
+        .headers
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+    {
+        span.record("resend.request_id", id);
+    }
+    if !response.status.is_success() {
+        let message = match serde_json::from_slice::<ErrorResponse>(&response.body) {
+            Ok(err) => format!("{}: {}", err.name, err.message),
+            Err(_) => format!("unexpected response status {}", response.status),
+        };
+        span.record("error", redact(&message, options).as_str());
+    }
+    if options.record_bodies {
+        span.record(
+            "response.body",
+            redact_body(&response.body, options).as_str(),
+        );
+    }
+}
+
+fn redact(value: &str, options: &TraceOptions) -> String {
+    if options.record_recipients {
+        value.to_string()
+    } else {
+        redact_addresses(value)
+    }
+}
+
+fn redact_body(body: &[u8], options: &TraceOptions) -> String {
+    redact(&String::from_utf8_lossy(body), options)
+}
+
+// `to`, `cc` and `bcc` of a send or every email of a batch, `None` for other requests
+fn recipients(request: &TransportRequest, options: &TraceOptions) -> Option<String> {
+    let body = request.json_body()?;
+    let emails = match &body {
+        serde_json::Value::Array(emails) => emails.iter().collect(),
+        email => vec![email],
+    };
+    let addresses: Vec<String> = emails
+        .iter()
+        .flat_map(|email| ["to", "cc", "bcc"].map(|field| &email[field]))
+        .flat_map(|value| match value {
+            serde_json::Value::Array(values) => values.iter().filter_map(|v| v.as_str()).collect(),
+            value => value.as_str().into_iter().collect::<Vec<_>>(),
+        })
+        .map(|address| redact(address, options))
+        .collect();
+    if addresses.is_empty() {
+        None
+    } else {
+        Some(addresses.join(", "))
+    }
+}
@@ -0,0 +1,63 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use http::{HeaderMap, Method, StatusCode};
+
+use crate::reqlib::Error;
+
+#[derive(Debug, Clone)]
+pub struct TransportRequest {
+    pub method: Method,
+    pub url: url::Url,
+    pub headers: HeaderMap,
+    pub body: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TransportResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+pub type TransportFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<TransportResponse, Error>> + Send + 'a>>;
+
+// a transport only moves bytes; auth, headers and response parsing stay in `ReqClient`
+pub trait Transport: Send + Sync {
+    fn send(&self, request: TransportRequest) -> TransportFuture<'_>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    pub client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        ReqwestTransport { client }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(&self, request: TransportRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let mut req = self
+                .client
+                .request(request.method, request.url)
+                .headers(request.headers);
+            if let Some(body) = request.body {
+                req = req.body(body);
+            }
+            let response = req.send().await?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            let body = response.bytes().await?.to_vec();
+            Ok(TransportResponse {
+                status,
+                headers,
+                body,
+            })
+        })
+    }
+}